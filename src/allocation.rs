@@ -0,0 +1,61 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Policies to calculate how many tokens each claimer receives.
+
+use alloy_primitives::U256;
+use linera_sdk::linera_base_types::Amount;
+use serde::{Deserialize, Serialize};
+
+/// The policy used to calculate the [`Amount`] airdropped to a claimer based on its snapshot
+/// balance.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AllocationPolicy {
+    /// Every eligible claimer receives the same amount.
+    Flat(Amount),
+    /// Claimers receive `rate` tokens for each `unit` of their snapshot balance.
+    Proportional { rate: Amount, unit: U256 },
+    /// Claimers receive the amount of the highest tier whose minimum balance they have.
+    Tiered(Vec<AllocationTier>),
+    /// Limits the amount calculated by another policy to a maximum `cap`.
+    Capped {
+        policy: Box<AllocationPolicy>,
+        cap: Amount,
+    },
+}
+
+/// A bracket of a [`AllocationPolicy::Tiered`] policy.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AllocationTier {
+    pub minimum_balance: U256,
+    pub amount: Amount,
+}
+
+impl AllocationPolicy {
+    /// Checks if the policy can be used to calculate amounts.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            AllocationPolicy::Flat(_) | AllocationPolicy::Tiered(_) => true,
+            AllocationPolicy::Proportional { unit, .. } => !unit.is_zero(),
+            AllocationPolicy::Capped { policy, .. } => policy.is_valid(),
+        }
+    }
+
+    /// Calculates the [`Amount`] to airdrop to a claimer with the specified snapshot `balance`.
+    pub fn amount_for(&self, balance: U256) -> Amount {
+        match self {
+            AllocationPolicy::Flat(amount) => *amount,
+            AllocationPolicy::Proportional { rate, unit } => {
+                let attos = balance.saturating_mul(U256::from(u128::from(*rate))) / unit;
+
+                Amount::from_attos(u128::try_from(attos).unwrap_or(u128::MAX))
+            }
+            AllocationPolicy::Tiered(tiers) => tiers
+                .iter()
+                .filter(|tier| balance >= tier.minimum_balance)
+                .max_by_key(|tier| tier.minimum_balance)
+                .map_or(Amount::ZERO, |tier| tier.amount),
+            AllocationPolicy::Capped { policy, cap } => policy.amount_for(balance).min(*cap),
+        }
+    }
+}
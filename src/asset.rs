@@ -0,0 +1,111 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The assets whose balances at the snapshot block decide who is eligible for the airdrop.
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::{sol, SolCall};
+use serde::{Deserialize, Serialize};
+
+use crate::SqlValue;
+
+/// The Space-and-Time table with the native balances of wallets, in each chain's schema.
+const NATIVE_BALANCES_TABLE: &str = "NATIVE_WALLETS";
+
+/// The Space-and-Time table with the ERC-20 token balances of wallets, in each chain's schema.
+const ERC20_BALANCES_TABLE: &str = "FUNGIBLETOKEN_WALLETS";
+
+/// The Space-and-Time table with the number of ERC-721 tokens held by wallets, in each chain's
+/// schema.
+const ERC721_BALANCES_TABLE: &str = "NFT_WALLETS";
+
+/// The Space-and-Time table with the ERC-1155 token balances of wallets, in each chain's schema.
+const ERC1155_BALANCES_TABLE: &str = "ERC1155_WALLETS";
+
+sol! {
+    /// The `balanceOf` function shared by ERC-20 tokens and ERC-721 collections.
+    interface IERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+    }
+
+    /// The `balanceOf` function of ERC-1155 collections.
+    interface IERC1155 {
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+    }
+}
+
+/// The asset held by claimers at the snapshot block.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SnapshotAsset {
+    /// The native currency of the chain.
+    Native,
+    /// The ERC-20 token deployed at the `contract` address.
+    Erc20 { contract: Address },
+    /// The ERC-721 collection deployed at the `collection` address, where the balance is the
+    /// number of tokens held.
+    Erc721 { collection: Address },
+    /// The token with the `token_id` in the ERC-1155 collection deployed at the `collection`
+    /// address.
+    Erc1155 { collection: Address, token_id: U256 },
+}
+
+impl SnapshotAsset {
+    /// Returns the Space-and-Time table with the balances of this asset, without the chain's
+    /// schema.
+    pub(crate) fn balances_table(&self) -> &'static str {
+        match self {
+            SnapshotAsset::Native => NATIVE_BALANCES_TABLE,
+            SnapshotAsset::Erc20 { .. } => ERC20_BALANCES_TABLE,
+            SnapshotAsset::Erc721 { .. } => ERC721_BALANCES_TABLE,
+            SnapshotAsset::Erc1155 { .. } => ERC1155_BALANCES_TABLE,
+        }
+    }
+
+    /// Returns the columns and values that select the rows of this asset in its balances table.
+    pub(crate) fn balances_filters(&self) -> Vec<(&'static str, SqlValue)> {
+        match *self {
+            SnapshotAsset::Native => vec![],
+            SnapshotAsset::Erc20 { contract } => {
+                vec![("TOKEN_ADDRESS", SqlValue::Address(contract))]
+            }
+            SnapshotAsset::Erc721 { collection } => {
+                vec![("CONTRACT_ADDRESS", SqlValue::Address(collection))]
+            }
+            SnapshotAsset::Erc1155 {
+                collection,
+                token_id,
+            } => vec![
+                ("CONTRACT_ADDRESS", SqlValue::Address(collection)),
+                ("TOKEN_ID", SqlValue::Text(token_id.to_string())),
+            ],
+        }
+    }
+
+    /// Returns the contract address and the call data to query the balance of `holder` using an
+    /// `eth_call`, or [`None`] for the native currency.
+    pub(crate) fn balance_call(&self, holder: Address) -> Option<(Address, Vec<u8>)> {
+        match *self {
+            SnapshotAsset::Native => None,
+            SnapshotAsset::Erc20 {
+                contract: token_contract,
+            }
+            | SnapshotAsset::Erc721 {
+                collection: token_contract,
+            } => Some((
+                token_contract,
+                IERC20::balanceOfCall { owner: holder }.abi_encode(),
+            )),
+            SnapshotAsset::Erc1155 {
+                collection,
+                token_id,
+            } => Some((
+                collection,
+                IERC1155::balanceOfCall {
+                    account: holder,
+                    id: token_id,
+                }
+                .abi_encode(),
+            )),
+        }
+    }
+}
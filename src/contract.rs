@@ -7,17 +7,28 @@
 mod contract_unit_tests;
 mod state;
 
-use airdrop_demo::{AirDropClaim, AirDropId, Parameters};
-use alloy_primitives::Address;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+use airdrop_demo::{
+    AirDropClaim, AirDropError, AirDropId, AirDropResponse, AllowlistOracle, ClaimReceipt,
+    ClaimRevocation, EligibilityOracle, EligibilitySource, MerkleOracle, Operation, Parameters,
+    RelayerFee, RuleEvaluation, MAX_CLAIM_BATCH_SIZE,
+};
+use alloy_primitives::{Address, U256};
 use linera_sdk::{
-    abis::fungible::{self, Account},
-    linera_base_types::{AccountOwner, Amount, WithContractAbi},
+    abis::fungible::{self, Account, FungibleResponse},
+    ensure,
+    linera_base_types::{AccountOwner, Amount, ChainId, WithContractAbi},
+    serde_json,
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 use serde::{Deserialize, Serialize};
 
-use self::state::Application;
+use self::state::{Application, ClaimRecord, ClaimStatus};
 
 pub struct ApplicationContract {
     state: Application,
@@ -31,7 +42,7 @@ impl WithContractAbi for ApplicationContract {
 }
 
 impl Contract for ApplicationContract {
-    type Message = ApprovedAirDrop;
+    type Message = Message;
     type EventValue = ();
     type Parameters = Parameters;
     type InstantiationArgument = ();
@@ -47,105 +58,1144 @@ impl Contract for ApplicationContract {
     ///
     /// Fails if the [`Parameters`] specified to create the application are invalid.
     async fn instantiate(&mut self, _argument: Self::InstantiationArgument) {
-        // Fetch the parameters to check that they can be successfully deserialized.
-        let _parameters = self.runtime.application_parameters();
+        let parameters = self.runtime.application_parameters();
+        let creator_chain = self.runtime.application_creator_chain_id();
+
+        parameters
+            .validate()
+            .unwrap_or_else(|error| panic!("{error}"));
+
+        if parameters
+            .shards
+            .iter()
+            .any(|shard| shard.chain_id == creator_chain)
+        {
+            panic!("{}", AirDropError::ShardOnCreatorChain);
+        }
     }
 
-    /// Verifies an [`AirDropClaim`][`zk_airdrop_demo::AirDropClaim`] and if approved, sends a
-    /// message to the application's creator chain to ask the tokens to be delivered.
-    async fn execute_operation(&mut self, claim: Self::Operation) -> Self::Response {
-        let creator_chain = self.runtime.application_creator_chain_id();
-        let amount = self.airdrop_amount(&claim).await;
+    /// Executes an [`Operation`], aborting the execution if it is rejected.
+    async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.try_execute_operation(operation)
+            .await
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Executes a [`Message`], aborting the execution if it is rejected.
+    async fn execute_message(&mut self, message: Self::Message) {
+        self.try_execute_message(message)
+            .await
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl ApplicationContract {
+    /// Executes an [`Operation`].
+    async fn try_execute_operation(
+        &mut self,
+        operation: Operation,
+    ) -> Result<AirDropResponse, AirDropError> {
+        match operation {
+            Operation::Claim(claim) => Ok(AirDropResponse::Claim(self.claim(claim).await?)),
+            Operation::ClaimBatch(claims) => {
+                Ok(AirDropResponse::ClaimBatch(self.claim_batch(claims).await?))
+            }
+            Operation::Sweep { target } => {
+                self.sweep(target).await?;
+                Ok(AirDropResponse::Ok)
+            }
+            Operation::Pause => {
+                self.set_paused(true)?;
+                Ok(AirDropResponse::Ok)
+            }
+            Operation::Resume => {
+                self.set_paused(false)?;
+                Ok(AirDropResponse::Ok)
+            }
+            Operation::FundShards => {
+                self.fund_shards().await?;
+                Ok(AirDropResponse::Ok)
+            }
+            Operation::Rebalance { shard } => {
+                self.request_rebalance(shard)?;
+                Ok(AirDropResponse::Ok)
+            }
+            Operation::RevokeClaims(revocation) => {
+                self.revoke_claims(revocation)?;
+                Ok(AirDropResponse::Ok)
+            }
+        }
+    }
+
+    /// Executes a [`Message`].
+    async fn try_execute_message(&mut self, message: Message) -> Result<(), AirDropError> {
+        match message {
+            Message::ApprovedAirDrop(airdrop) => match self.pay(airdrop).await {
+                Err(AirDropError::AlreadyPaid) => {
+                    *self.state.rejected_replays.get_mut() += 1;
+                    Ok(())
+                }
+                Err(AirDropError::ClaimRevoked) => {
+                    *self.state.rejected_revoked_claims.get_mut() += 1;
+                    Ok(())
+                }
+                result => result,
+            },
+            Message::ApprovedAirDropBatch(airdrops) => self.pay_batch(airdrops).await,
+            Message::ReturnSurplus => self.return_surplus(),
+            Message::SetPaused { paused } => self.apply_paused(paused),
+            Message::RevokeClaims { id, nonce } => self.record_revocation(id, nonce).await,
+        }
+    }
+
+    /// Checks that an `airdrop` hasn't been handled before, and if so delivers its tokens.
+    ///
+    /// Airdrops that have already been paid are rejected with [`AirDropError::AlreadyPaid`], and
+    /// airdrops of revoked claims with [`AirDropError::ClaimRevoked`], which the message handler
+    /// counts instead of rejecting the message.
+    async fn pay(&mut self, airdrop: ApprovedAirDrop) -> Result<(), AirDropError> {
+        self.check_not_paused()?;
+        self.check_claim_window_is_open()?;
+        self.check_payer_chain(&airdrop.id)?;
+
+        ensure!(!self.is_paid(&airdrop.id).await, AirDropError::AlreadyPaid);
+
+        self.check_not_revoked(&airdrop).await?;
+
+        let amount = self.withdraw_from_budget(airdrop.amount)?;
+        self.record_payment(&airdrop, amount).await;
+        self.deliver(amount, &airdrop);
+
+        Ok(())
+    }
+
+    /// Checks that none of the `airdrops` in a batch have been handled before, and if so delivers
+    /// their tokens.
+    ///
+    /// The airdrops are paid in order, like separate [`Message::ApprovedAirDrop`]s: the airdrop
+    /// that exhausts the remaining budget only receives the remainder of the budget, and the
+    /// airdrops after it aren't paid. The batch is rejected if the budget is already exhausted.
+    /// Airdrops that have already been paid and airdrops of revoked claims are skipped and counted,
+    /// without rejecting the rest of the batch.
+    async fn pay_batch(&mut self, airdrops: Vec<ApprovedAirDrop>) -> Result<(), AirDropError> {
+        self.check_not_paused()?;
+        self.check_claim_window_is_open()?;
+
+        let mut pending = Vec::with_capacity(airdrops.len());
+        let mut pending_claimers = BTreeSet::new();
+        let mut replays = 0;
+        let mut revoked = 0;
+
+        for airdrop in airdrops {
+            self.check_payer_chain(&airdrop.id)?;
+
+            if self.is_paid(&airdrop.id).await
+                || !pending_claimers.insert(airdrop.id.external_address())
+            {
+                replays += 1;
+            } else if self.is_revoked(&airdrop).await {
+                revoked += 1;
+            } else {
+                pending.push(airdrop);
+            }
+        }
+
+        ensure!(
+            pending.is_empty() || self.remaining_budget() > Amount::ZERO,
+            AirDropError::BudgetExhausted
+        );
+
+        *self.state.rejected_replays.get_mut() += replays;
+        *self.state.rejected_revoked_claims.get_mut() += revoked;
+
+        for airdrop in pending {
+            if self.remaining_budget() == Amount::ZERO {
+                break;
+            }
+
+            let amount = self.withdraw_from_budget(airdrop.amount)?;
+            self.record_payment(&airdrop, amount).await;
+            self.deliver(amount, &airdrop);
+        }
+
+        Ok(())
+    }
+
+    /// Verifies an [`AirDropClaim`] and if approved, sends a message to the chain responsible for
+    /// paying the claimer to ask the tokens to be delivered.
+    ///
+    /// Returns a [`ClaimReceipt`] describing the approved claim.
+    async fn claim(&mut self, claim: AirDropClaim) -> Result<ClaimReceipt, AirDropError> {
+        self.check_not_paused()?;
+        self.check_claim_window_is_open()?;
+
+        self.check_claim_deadline(&claim)?;
+
         let application_id = self.runtime.application_id();
-        let claimer = claim
-            .signer_address(application_id)
-            .expect("Failed to verify signature");
+        let parameters = self.runtime.application_parameters();
+        let claimer = claim.signer_address(application_id, parameters.source_chain)?;
+        let payer_chain = parameters
+            .payer_chain(&claimer)
+            .unwrap_or_else(|| self.runtime.application_creator_chain_id());
+
+        let balance = self.check_eligibility(&claimer, &claim)?;
 
-        self.assert_eligibility(&claimer, &claim.api_token);
+        if parameters.rule.is_some() {
+            self.check_rule(&claimer, &claim.api_token)?;
+        }
+
+        let amount = self.airdrop_amount(balance)?;
+
+        check_relayer_fee(&claim, amount)?;
 
         self.runtime
-            .prepare_message(ApprovedAirDrop {
+            .prepare_message(Message::ApprovedAirDrop(ApprovedAirDrop {
+                id: claimer.into(),
+                amount,
+                destination: claim.destination,
+                nonce: claim.nonce,
+                relayer_fee: claim.relayer_fee,
+            }))
+            .with_authentication()
+            .send_to(payer_chain);
+
+        Ok(ClaimReceipt {
+            claimer,
+            amount,
+            payer_chain,
+        })
+    }
+
+    /// Verifies a batch of [`AirDropClaim`]s and if all of them are approved, sends a single
+    /// message to the chain responsible for paying the claimers to ask the tokens to be delivered.
+    ///
+    /// The snapshot balances of all claimers are checked with a single query to the service, using
+    /// the API token of the first claim.
+    ///
+    /// Returns a [`ClaimReceipt`] for each claim, in the same order as the `claims`.
+    async fn claim_batch(
+        &mut self,
+        claims: Vec<AirDropClaim>,
+    ) -> Result<Vec<ClaimReceipt>, AirDropError> {
+        self.check_not_paused()?;
+        self.check_claim_window_is_open()?;
+
+        ensure!(
+            (1..=MAX_CLAIM_BATCH_SIZE).contains(&claims.len()),
+            AirDropError::InvalidClaimBatchSize(MAX_CLAIM_BATCH_SIZE)
+        );
+
+        for claim in &claims {
+            self.check_claim_deadline(claim)?;
+        }
+
+        let application_id = self.runtime.application_id();
+        let parameters = self.runtime.application_parameters();
+        let claimers = claims
+            .iter()
+            .map(|claim| claim.signer_address(application_id, parameters.source_chain))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ensure!(
+            claimers.iter().collect::<BTreeSet<_>>().len() == claimers.len(),
+            AirDropError::DuplicateClaimInBatch
+        );
+
+        let creator_chain = self.runtime.application_creator_chain_id();
+        let payer_chain = parameters
+            .payer_chain(&claimers[0])
+            .unwrap_or(creator_chain);
+
+        ensure!(
+            claimers.iter().all(|claimer| {
+                parameters.payer_chain(claimer).unwrap_or(creator_chain) == payer_chain
+            }),
+            AirDropError::MixedPayerChains
+        );
+
+        let balances = self.check_batch_eligibility(&claimers, &claims)?;
+
+        if parameters.rule.is_some() {
+            self.check_batch_rule(&claimers, &claims[0].api_token)?;
+        }
+
+        let mut airdrops = Vec::with_capacity(claims.len());
+        let mut receipts = Vec::with_capacity(claims.len());
+
+        for ((claimer, claim), balance) in claimers.into_iter().zip(claims).zip(balances) {
+            let amount = self.airdrop_amount(balance)?;
+
+            check_relayer_fee(&claim, amount)?;
+
+            airdrops.push(ApprovedAirDrop {
                 id: claimer.into(),
                 amount,
                 destination: claim.destination,
+                nonce: claim.nonce,
+                relayer_fee: claim.relayer_fee,
+            });
+            receipts.push(ClaimReceipt {
+                claimer,
+                amount,
+                payer_chain,
+            });
+        }
+
+        self.runtime
+            .prepare_message(Message::ApprovedAirDropBatch(airdrops))
+            .with_authentication()
+            .send_to(payer_chain);
+
+        Ok(receipts)
+    }
+
+    /// Verifies a [`ClaimRevocation`] and sends it to the chain responsible for paying the
+    /// claimer, which stops paying the claimer's airdrop claims with a lower nonce.
+    fn revoke_claims(&mut self, revocation: ClaimRevocation) -> Result<(), AirDropError> {
+        let application_id = self.runtime.application_id();
+        let parameters = self.runtime.application_parameters();
+        let claimer = revocation.signer_address(application_id, parameters.source_chain)?;
+        let payer_chain = parameters
+            .payer_chain(&claimer)
+            .unwrap_or_else(|| self.runtime.application_creator_chain_id());
+
+        self.runtime
+            .prepare_message(Message::RevokeClaims {
+                id: claimer.into(),
+                nonce: revocation.nonce,
             })
             .with_authentication()
-            .send_to(creator_chain);
+            .send_to(payer_chain);
+
+        Ok(())
     }
 
-    /// Checks that an `airdrop` hasn't been handled before, and if so delivers its tokens.
-    async fn execute_message(&mut self, airdrop: Self::Message) {
-        self.track_claim(&airdrop.id).await;
+    /// Records that the airdrop claims of the claimer identified by `id` with a nonce lower than
+    /// `nonce` have been revoked.
+    ///
+    /// Revocations never lower the nonce that was already recorded.
+    async fn record_revocation(&mut self, id: AirDropId, nonce: u64) -> Result<(), AirDropError> {
+        self.check_payer_chain(&id)?;
+
+        let minimum_nonce = self
+            .state
+            .minimum_claim_nonces
+            .get_mut_or_default(&id)
+            .await
+            .expect("Failed to read claim nonces from storage");
+
+        *minimum_nonce = (*minimum_nonce).max(nonce);
+
+        Ok(())
+    }
+
+    /// Transfers the tokens that were not claimed to the `target` account.
+    async fn sweep(&mut self, target: Account) -> Result<(), AirDropError> {
+        let claim_end = self.runtime.application_parameters().claim_end;
+
+        self.check_admin_on_creator_chain()?;
+        ensure!(
+            self.runtime.system_time() >= claim_end,
+            AirDropError::ClaimWindowStillOpen
+        );
+
+        let amount = self.token_balance()?;
+
+        if amount > Amount::ZERO {
+            self.transfer_tokens(amount, target);
+        }
+
+        Ok(())
+    }
+
+    /// Transfers tokens to each shard until it has been funded with its budget, or until the
+    /// creator chain's tokens run out.
+    ///
+    /// The tokens needed to pay the rest of the creator chain's own budget are kept.
+    async fn fund_shards(&mut self) -> Result<(), AirDropError> {
+        self.check_admin_on_creator_chain()?;
+
+        let shards = self.runtime.application_parameters().shards;
+        let application_owner = AccountOwner::from(self.runtime.application_id());
+        let mut available = self
+            .token_balance()?
+            .saturating_sub(self.remaining_budget());
+
+        for shard in shards {
+            let funded = self
+                .state
+                .shard_funding
+                .get_mut_or_default(&shard.chain_id)
+                .await
+                .expect("Failed to read shard funding from storage");
+            let amount = shard.budget.saturating_sub(*funded).min(available);
+
+            if amount > Amount::ZERO {
+                funded.saturating_add_assign(amount);
+                available = available.saturating_sub(amount);
+
+                self.transfer_tokens(
+                    amount,
+                    Account {
+                        chain_id: shard.chain_id,
+                        owner: application_owner,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Requests a `shard` to return its surplus tokens to the creator chain.
+    fn request_rebalance(&mut self, shard: ChainId) -> Result<(), AirDropError> {
+        self.check_admin_on_creator_chain()?;
 
         let parameters = self.runtime.application_parameters();
-        let source_account = AccountOwner::from(self.runtime.application_id());
 
+        ensure!(
+            parameters
+                .shards
+                .iter()
+                .any(|payer| payer.chain_id == shard),
+            AirDropError::UnknownShard
+        );
+
+        self.runtime
+            .prepare_message(Message::ReturnSurplus)
+            .with_authentication()
+            .send_to(shard);
+
+        Ok(())
+    }
+
+    /// Returns the tokens this shard doesn't need to pay its remaining budget to the creator
+    /// chain.
+    ///
+    /// After the claim window ends, all of the shard's tokens are returned.
+    fn return_surplus(&mut self) -> Result<(), AirDropError> {
+        self.check_message_from_creator_chain(AirDropError::UnauthorizedRebalance)?;
+
+        let creator_chain = self.runtime.application_creator_chain_id();
+        let chain_id = self.runtime.chain_id();
+        let parameters = self.runtime.application_parameters();
+        let reserved = if self.runtime.system_time() < parameters.claim_end {
+            parameters
+                .payer_budget(chain_id)
+                .saturating_sub(*self.state.distributed_total.get())
+        } else {
+            Amount::ZERO
+        };
+        let surplus = self.token_balance()?.saturating_sub(reserved);
+
+        if surplus > Amount::ZERO {
+            let application_owner = AccountOwner::from(self.runtime.application_id());
+
+            self.transfer_tokens(
+                surplus,
+                Account {
+                    chain_id: creator_chain,
+                    owner: application_owner,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [`Amount`] of tokens held by the application on the current chain.
+    fn token_balance(&mut self) -> Result<Amount, AirDropError> {
+        let token_id = self.runtime.application_parameters().token_id;
+        let owner = AccountOwner::from(self.runtime.application_id());
+
+        match self
+            .runtime
+            .call_application(true, token_id, &fungible::Operation::Balance { owner })
+        {
+            FungibleResponse::Balance(amount) => Ok(amount),
+            _ => Err(AirDropError::UnexpectedTokenResponse),
+        }
+    }
+
+    /// Delivers the paid `amount` of an `airdrop`, paying its relayer fee to the relayer and the
+    /// rest to the claimer's destination.
+    ///
+    /// If the `amount` was reduced by the remaining budget, the relayer fee is paid first.
+    fn deliver(&mut self, amount: Amount, airdrop: &ApprovedAirDrop) {
+        let mut claimer_amount = amount;
+
+        if let Some(relayer_fee) = airdrop.relayer_fee {
+            let relayer_amount = relayer_fee.amount.min(amount);
+            claimer_amount = amount.saturating_sub(relayer_amount);
+
+            if relayer_amount > Amount::ZERO {
+                self.transfer_tokens(relayer_amount, relayer_fee.relayer);
+            }
+        }
+
+        if claimer_amount > Amount::ZERO {
+            self.transfer_tokens(claimer_amount, airdrop.destination);
+        }
+    }
+
+    /// Transfers an `amount` of the application's tokens on the current chain to a `target`
+    /// account.
+    fn transfer_tokens(&mut self, amount: Amount, target: Account) {
+        let token_id = self.runtime.application_parameters().token_id;
         let transfer = fungible::Operation::Transfer {
-            owner: source_account,
-            amount: airdrop.amount,
-            target_account: airdrop.destination,
+            owner: AccountOwner::from(self.runtime.application_id()),
+            amount,
+            target_account: target,
         };
 
-        self.runtime
-            .call_application(true, parameters.token_id, &transfer);
+        self.runtime.call_application(true, token_id, &transfer);
     }
 
-    async fn store(mut self) {
-        self.state.save().await.expect("Failed to save state");
+    /// Pauses or resumes the airdrop on the creator chain, and sends the new state to every shard.
+    fn set_paused(&mut self, paused: bool) -> Result<(), AirDropError> {
+        self.check_admin_on_creator_chain()?;
+        self.state.paused.set(paused);
+
+        for shard in self.runtime.application_parameters().shards {
+            self.runtime
+                .prepare_message(Message::SetPaused { paused })
+                .with_authentication()
+                .send_to(shard.chain_id);
+        }
+
+        Ok(())
+    }
+
+    /// Pauses or resumes the airdrop on this shard, as requested by the creator chain.
+    fn apply_paused(&mut self, paused: bool) -> Result<(), AirDropError> {
+        self.check_message_from_creator_chain(AirDropError::UnauthorizedPause)?;
+        self.state.paused.set(paused);
+        Ok(())
+    }
+
+    /// Checks that the message being executed was sent by the creator chain, failing with the
+    /// `error` otherwise.
+    fn check_message_from_creator_chain(
+        &mut self,
+        error: AirDropError,
+    ) -> Result<(), AirDropError> {
+        let creator_chain = self.runtime.application_creator_chain_id();
+        let origin_chain = self
+            .runtime
+            .message_id()
+            .map(|message_id| message_id.chain_id);
+
+        ensure!(origin_chain == Some(creator_chain), error);
+
+        Ok(())
+    }
+
+    /// Checks that the operation is executed on the creator chain and signed by the admin.
+    fn check_admin_on_creator_chain(&mut self) -> Result<(), AirDropError> {
+        let admin = self.runtime.application_parameters().admin;
+
+        ensure!(
+            self.runtime.chain_id() == self.runtime.application_creator_chain_id(),
+            AirDropError::NotCreatorChain
+        );
+        ensure!(
+            self.runtime.authenticated_signer() == Some(admin),
+            AirDropError::Unauthorized
+        );
+
+        Ok(())
+    }
+
+    /// Checks that the airdrop has not been paused.
+    ///
+    /// The airdrop is paused on the creator chain and on every shard, which receive the state from
+    /// the creator chain. Claims verified on other chains are rejected once their approval reaches
+    /// the chain that pays them.
+    fn check_not_paused(&self) -> Result<(), AirDropError> {
+        ensure!(!*self.state.paused.get(), AirDropError::Paused);
+        Ok(())
+    }
+
+    /// Checks that the current chain is responsible for paying the `airdrop`.
+    fn check_payer_chain(&mut self, airdrop: &AirDropId) -> Result<(), AirDropError> {
+        let payer_chain = self
+            .runtime
+            .application_parameters()
+            .payer_chain(&airdrop.external_address())
+            .unwrap_or_else(|| self.runtime.application_creator_chain_id());
+
+        ensure!(
+            self.runtime.chain_id() == payer_chain,
+            AirDropError::WrongPayerChain
+        );
+
+        Ok(())
+    }
+
+    /// Checks that the current block's timestamp is not after the deadline signed in the `claim`.
+    fn check_claim_deadline(&mut self, claim: &AirDropClaim) -> Result<(), AirDropError> {
+        ensure!(
+            self.runtime.system_time() <= claim.deadline,
+            AirDropError::ClaimExpired
+        );
+
+        Ok(())
+    }
+
+    /// Checks that the claim of an `airdrop` hasn't been revoked by its claimer.
+    async fn check_not_revoked(&self, airdrop: &ApprovedAirDrop) -> Result<(), AirDropError> {
+        ensure!(!self.is_revoked(airdrop).await, AirDropError::ClaimRevoked);
+
+        Ok(())
+    }
+
+    /// Checks if the claim of an `airdrop` was revoked by its claimer.
+    async fn is_revoked(&self, airdrop: &ApprovedAirDrop) -> bool {
+        let minimum_nonce = self
+            .state
+            .minimum_claim_nonces
+            .get(&airdrop.id)
+            .await
+            .expect("Failed to read claim nonces from storage")
+            .unwrap_or_default();
+
+        airdrop.nonce < minimum_nonce
+    }
+
+    /// Checks that the current block's timestamp is inside the airdrop's claim window.
+    fn check_claim_window_is_open(&mut self) -> Result<(), AirDropError> {
+        let Parameters {
+            claim_start,
+            claim_end,
+            ..
+        } = self.runtime.application_parameters();
+        let now = self.runtime.system_time();
+
+        ensure!(now >= claim_start, AirDropError::ClaimWindowNotStarted);
+        ensure!(now < claim_end, AirDropError::ClaimWindowEnded);
+
+        Ok(())
+    }
+
+    /// Checks that an [`Address`] is eligible for an airdrop, using the [`EligibilityOracle`]
+    /// selected in the [`Parameters`] and the evidence included in the `claim`.
+    ///
+    /// Returns the address's balance at the snapshot block.
+    pub fn check_eligibility(
+        &mut self,
+        address: &Address,
+        claim: &AirDropClaim,
+    ) -> Result<U256, AirDropError> {
+        let Parameters {
+            minimum_balance,
+            eligibility,
+            ..
+        } = self.runtime.application_parameters();
+
+        let balance = match &eligibility {
+            EligibilitySource::SpaceAndTime | EligibilitySource::JsonRpc { .. } => {
+                ServiceOracle::new(&mut self.runtime, &claim.api_token).snapshot_balance(address)?
+            }
+            EligibilitySource::Allowlist(balances) => {
+                AllowlistOracle::new(balances).snapshot_balance(address)?
+            }
+            EligibilitySource::MerkleRoot(root) => {
+                MerkleOracle::new(*root, claim.merkle_proof.as_ref()).snapshot_balance(address)?
+            }
+        };
+
+        ensure!(
+            balance >= minimum_balance,
+            AirDropError::InsufficientBalance
+        );
+
+        Ok(balance)
+    }
+
+    /// Checks that all `claimers` are eligible for an airdrop, using the [`EligibilityOracle`]
+    /// selected in the [`Parameters`] and the evidence included in their `claims`.
+    ///
+    /// The balances queried by the service are checked with a single query for all claimers.
+    ///
+    /// Returns the claimers' balances at the snapshot block, in the same order as the `claimers`.
+    fn check_batch_eligibility(
+        &mut self,
+        claimers: &[Address],
+        claims: &[AirDropClaim],
+    ) -> Result<Vec<U256>, AirDropError> {
+        let Parameters {
+            minimum_balance,
+            eligibility,
+            ..
+        } = self.runtime.application_parameters();
+
+        match eligibility {
+            EligibilitySource::SpaceAndTime | EligibilitySource::JsonRpc { .. } => {
+                let balances = ServiceOracle::new(&mut self.runtime, &claims[0].api_token)
+                    .snapshot_balances(claimers)?;
+
+                claimers
+                    .iter()
+                    .map(|claimer| {
+                        let balance = *balances.get(claimer).ok_or(AirDropError::NotEligible)?;
+
+                        ensure!(
+                            balance >= minimum_balance,
+                            AirDropError::InsufficientBalance
+                        );
+
+                        Ok(balance)
+                    })
+                    .collect()
+            }
+            EligibilitySource::Allowlist(_) | EligibilitySource::MerkleRoot(_) => claimers
+                .iter()
+                .zip(claims)
+                .map(|(claimer, claim)| self.check_eligibility(claimer, claim))
+                .collect(),
+        }
+    }
+
+    /// Checks that an [`Address`] satisfies the eligibility rule in the [`Parameters`], which is
+    /// evaluated by the service.
+    fn check_rule(&mut self, address: &Address, api_token: &str) -> Result<(), AirDropError> {
+        let evaluation = ServiceOracle::new(&mut self.runtime, api_token).evaluate_rule(address)?;
+
+        ensure!(
+            evaluation.satisfied,
+            AirDropError::RuleNotSatisfied(evaluation.explanation)
+        );
+
+        Ok(())
+    }
+
+    /// Checks that all the `claimers` in a batch satisfy the eligibility rule in the
+    /// [`Parameters`], which is evaluated by the service with a single query.
+    fn check_batch_rule(
+        &mut self,
+        claimers: &[Address],
+        api_token: &str,
+    ) -> Result<(), AirDropError> {
+        let evaluations =
+            ServiceOracle::new(&mut self.runtime, api_token).evaluate_rules(claimers)?;
+
+        for evaluation in evaluations {
+            ensure!(
+                evaluation.satisfied,
+                AirDropError::RuleNotSatisfied(evaluation.explanation)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Calculates the [`Amount`] to be airdropped to a claimer with the snapshot `balance`.
+    ///
+    /// Claims for which the allocation policy calculates no tokens are rejected.
+    fn airdrop_amount(&mut self, balance: U256) -> Result<Amount, AirDropError> {
+        let amount = self
+            .runtime
+            .application_parameters()
+            .allocation
+            .amount_for(balance);
+
+        ensure!(amount > Amount::ZERO, AirDropError::ZeroAirdropAmount);
+
+        Ok(amount)
+    }
+
+    /// Checks if an airdrop has already been paid.
+    async fn is_paid(&self, airdrop: &AirDropId) -> bool {
+        self.state
+            .handled_airdrops
+            .contains_key(airdrop)
+            .await
+            .expect("Failed to read handled claims from storage")
+    }
+
+    /// Records the payment of an `airdrop` with the paid `amount` in the claim history and in the
+    /// campaign statistics.
+    async fn record_payment(&mut self, airdrop: &ApprovedAirDrop, amount: Amount) {
+        let status = if amount < airdrop.amount {
+            ClaimStatus::PartiallyPaid
+        } else {
+            ClaimStatus::Paid
+        };
+
+        let record = ClaimRecord {
+            status,
+            amount,
+            destination: airdrop.destination,
+            block_height: self.runtime.block_height(),
+            timestamp: self.runtime.system_time(),
+        };
+
+        self.state
+            .handled_airdrops
+            .insert(&airdrop.id, record)
+            .expect("Failed to write handled claim to storage");
+
+        *self.state.claims_paid.get_mut() += 1;
+
+        let destination_chain = airdrop.destination.chain_id;
+        let is_new_destination_chain = !self
+            .state
+            .destination_chains
+            .contains(&destination_chain)
+            .await
+            .expect("Failed to read destination chains from storage");
+
+        if is_new_destination_chain {
+            self.state
+                .destination_chains
+                .insert(&destination_chain)
+                .expect("Failed to write destination chain to storage");
+            *self.state.destination_chain_count.get_mut() += 1;
+        }
+    }
+
+    /// Withdraws up to the `requested` [`Amount`] from the airdrop's budget, failing if the
+    /// budget has been exhausted.
+    ///
+    /// Returns the [`Amount`] that should be paid, which is smaller than the `requested` amount if
+    /// the remaining budget is not enough to cover it.
+    fn withdraw_from_budget(&mut self, requested: Amount) -> Result<Amount, AirDropError> {
+        let remaining = self.remaining_budget();
+
+        ensure!(remaining > Amount::ZERO, AirDropError::BudgetExhausted);
+
+        let amount = requested.min(remaining);
+        self.state
+            .distributed_total
+            .get_mut()
+            .saturating_add_assign(amount);
+
+        Ok(amount)
+    }
+
+    /// Returns the [`Amount`] of the current chain's budget that hasn't been distributed yet.
+    fn remaining_budget(&mut self) -> Amount {
+        let chain_id = self.runtime.chain_id();
+        let budget = self.runtime.application_parameters().payer_budget(chain_id);
+
+        budget.saturating_sub(*self.state.distributed_total.get())
     }
 }
 
-impl ApplicationContract {
-    /// Asserts that an [`Address`] is eligible for an airdrop.
-    pub fn assert_eligibility(&mut self, address: &Address, api_token: &str) {
-        let request = async_graphql::Request::new(format!(
-            r#"query {{ checkEligibility(address: "{address}", apiToken: "{api_token}") }}"#
-        ));
+/// Checks that the relayer fee of a `claim` can be paid from its airdropped `amount`.
+fn check_relayer_fee(claim: &AirDropClaim, amount: Amount) -> Result<(), AirDropError> {
+    ensure!(
+        claim
+            .relayer_fee
+            .is_none_or(|relayer_fee| relayer_fee.amount <= amount),
+        AirDropError::RelayerFeeExceedsAirdrop
+    );
+
+    Ok(())
+}
+
+/// The query sent to the service for the snapshot balance of an address.
+const CHECK_ELIGIBILITY_QUERY: &str = "\
+    query CheckEligibility($address: String!, $apiToken: String!) { \
+        checkEligibility(address: $address, apiToken: $apiToken) \
+    }";
+
+/// The query sent to the service to evaluate the eligibility rule for an address.
+const CHECK_RULE_QUERY: &str = "\
+    query CheckRule($address: String!, $apiToken: String!) { \
+        checkRule(address: $address, apiToken: $apiToken) { satisfied explanation } \
+    }";
+
+/// The query sent to the service to evaluate the eligibility rule for several addresses.
+const CHECK_RULE_BATCH_QUERY: &str = "\
+    query CheckRuleBatch($addresses: [String!]!, $apiToken: String!) { \
+        checkRuleBatch(addresses: $addresses, apiToken: $apiToken) { satisfied explanation } \
+    }";
+
+/// The query sent to the service for the snapshot balances of several addresses.
+const CHECK_ELIGIBILITY_BATCH_QUERY: &str = "\
+    query CheckEligibilityBatch($addresses: [String!]!, $apiToken: String!) { \
+        checkEligibilityBatch(addresses: $addresses, apiToken: $apiToken) { address balance } \
+    }";
+
+/// An [`EligibilityOracle`] that queries the application's service, which performs the HTTP
+/// requests to the source of the snapshot balances.
+///
+/// The claimer's API token and the queried addresses are always sent as GraphQL variables, so
+/// that they can't change the structure of the queries.
+struct ServiceOracle<'runtime, 'token> {
+    runtime: &'runtime mut ContractRuntime<ApplicationContract>,
+    api_token: &'token str,
+}
+
+impl<'runtime, 'token> ServiceOracle<'runtime, 'token> {
+    /// Creates a [`ServiceOracle`] that queries the service using the `runtime`, forwarding the
+    /// claimer's `api_token`.
+    fn new(
+        runtime: &'runtime mut ContractRuntime<ApplicationContract>,
+        api_token: &'token str,
+    ) -> Self {
+        ServiceOracle { runtime, api_token }
+    }
+
+    /// Queries the service to evaluate the eligibility rule for an `address`.
+    fn evaluate_rule(&mut self, address: &Address) -> Result<RuleEvaluation, AirDropError> {
+        let request = async_graphql::Request::new(CHECK_RULE_QUERY).variables(
+            async_graphql::Variables::from_json(serde_json::json!({
+                "address": address.to_string(),
+                "apiToken": self.api_token,
+            })),
+        );
 
         let application_id = self.runtime.application_id();
         let response = self.runtime.query_service(application_id, request);
 
-        let async_graphql::Value::Object(data_object) = response.data else {
-            panic!("Unexpected response from `checkEligibility: {response:?}`");
+        if let Some(error) = response.errors.first() {
+            return Err(AirDropError::OracleFailure(error.message.clone()));
+        }
+
+        let async_graphql::Value::Object(mut data_object) = response.data else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Unexpected response from `checkRule`: {response:?}"
+            )));
         };
 
-        let async_graphql::Value::Boolean(is_eligible) = data_object["checkEligibility"] else {
-            panic!("Missing `checkEligibility` result in response data: {data_object:?}");
+        let Some(evaluation) = data_object.swap_remove("checkRule") else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Missing `checkRule` result in response data: {data_object:?}"
+            )));
         };
 
-        assert!(is_eligible);
+        parse_rule_evaluation(evaluation, "checkRule")
     }
 
-    /// Calculates the [`Amount`] to be airdropped for one [`AirDropClaim`].
-    async fn airdrop_amount(&mut self, _claim: &AirDropClaim) -> Amount {
-        Amount::ONE
+    /// Queries the service to evaluate the eligibility rule for several `addresses` at once.
+    ///
+    /// Returns the evaluations in the same order as the `addresses`.
+    fn evaluate_rules(
+        &mut self,
+        addresses: &[Address],
+    ) -> Result<Vec<RuleEvaluation>, AirDropError> {
+        let request = async_graphql::Request::new(CHECK_RULE_BATCH_QUERY)
+            .variables(address_list_variables(addresses, self.api_token));
+
+        let application_id = self.runtime.application_id();
+        let response = self.runtime.query_service(application_id, request);
+
+        if let Some(error) = response.errors.first() {
+            return Err(AirDropError::OracleFailure(error.message.clone()));
+        }
+
+        let async_graphql::Value::Object(mut data_object) = response.data else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Unexpected response from `checkRuleBatch`: {response:?}"
+            )));
+        };
+
+        let Some(async_graphql::Value::List(evaluations)) =
+            data_object.swap_remove("checkRuleBatch")
+        else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Missing `checkRuleBatch` result in response data: {data_object:?}"
+            )));
+        };
+
+        ensure!(
+            evaluations.len() == addresses.len(),
+            AirDropError::MalformedOracleResponse(format!(
+                "`checkRuleBatch` returned {} evaluations for {} addresses",
+                evaluations.len(),
+                addresses.len()
+            ))
+        );
+
+        evaluations
+            .into_iter()
+            .map(|evaluation| parse_rule_evaluation(evaluation, "checkRuleBatch"))
+            .collect()
     }
 
-    /// Tracks a claim, aborting the execution if it has already been handled.
-    async fn track_claim(&mut self, airdrop: &AirDropId) {
-        assert!(
-            !self
-                .state
-                .handled_airdrops
-                .contains(airdrop)
-                .await
-                .expect("Failed to read handled claims from storage"),
-            "Airdrop has already been paid"
+    /// Queries the service for the snapshot balances of several `addresses` at once.
+    ///
+    /// Addresses that aren't part of the snapshot are missing from the returned map.
+    fn snapshot_balances(
+        &mut self,
+        addresses: &[Address],
+    ) -> Result<BTreeMap<Address, U256>, AirDropError> {
+        let request = async_graphql::Request::new(CHECK_ELIGIBILITY_BATCH_QUERY)
+            .variables(address_list_variables(addresses, self.api_token));
+
+        let application_id = self.runtime.application_id();
+        let response = self.runtime.query_service(application_id, request);
+
+        if let Some(error) = response.errors.first() {
+            return Err(AirDropError::OracleFailure(error.message.clone()));
+        }
+
+        let async_graphql::Value::Object(mut data_object) = response.data else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Unexpected response from `checkEligibilityBatch`: {response:?}"
+            )));
+        };
+
+        let Some(async_graphql::Value::List(entries)) =
+            data_object.swap_remove("checkEligibilityBatch")
+        else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Missing `checkEligibilityBatch` result in response data: {data_object:?}"
+            )));
+        };
+
+        let mut balances = BTreeMap::new();
+
+        for entry in entries {
+            let async_graphql::Value::Object(mut entry) = entry else {
+                return Err(AirDropError::MalformedOracleResponse(format!(
+                    "Invalid `checkEligibilityBatch` entry: {entry:?}"
+                )));
+            };
+
+            let (address, balance) =
+                match (entry.swap_remove("address"), entry.swap_remove("balance")) {
+                    (
+                        Some(async_graphql::Value::String(address)),
+                        Some(async_graphql::Value::String(balance)),
+                    ) => (Address::from_str(&address), Some(U256::from_str(&balance))),
+                    (
+                        Some(async_graphql::Value::String(address)),
+                        Some(async_graphql::Value::Null),
+                    ) => (Address::from_str(&address), None),
+                    _ => {
+                        return Err(AirDropError::MalformedOracleResponse(format!(
+                            "Invalid `checkEligibilityBatch` entry: {entry:?}"
+                        )))
+                    }
+                };
+
+            let address = address.map_err(|_| {
+                AirDropError::MalformedOracleResponse(
+                    "Invalid address in `checkEligibilityBatch` result".to_owned(),
+                )
+            })?;
+
+            if let Some(balance) = balance {
+                let balance = balance.map_err(|_| {
+                    AirDropError::MalformedOracleResponse(
+                        "Invalid balance in `checkEligibilityBatch` result".to_owned(),
+                    )
+                })?;
+
+                balances.insert(address, balance);
+            }
+        }
+
+        Ok(balances)
+    }
+}
+
+impl EligibilityOracle for ServiceOracle<'_, '_> {
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError> {
+        let request = async_graphql::Request::new(CHECK_ELIGIBILITY_QUERY).variables(
+            async_graphql::Variables::from_json(serde_json::json!({
+                "address": address.to_string(),
+                "apiToken": self.api_token,
+            })),
         );
 
-        self.state
-            .handled_airdrops
-            .insert(airdrop)
-            .expect("Failed to write handled claim to storage");
+        let application_id = self.runtime.application_id();
+        let response = self.runtime.query_service(application_id, request);
+
+        if let Some(error) = response.errors.first() {
+            return Err(AirDropError::OracleFailure(error.message.clone()));
+        }
+
+        let async_graphql::Value::Object(data_object) = response.data else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Unexpected response from `checkEligibility`: {response:?}"
+            )));
+        };
+
+        match data_object.get("checkEligibility") {
+            Some(async_graphql::Value::String(balance_string)) => U256::from_str(balance_string)
+                .map_err(|_| {
+                    AirDropError::MalformedOracleResponse(format!(
+                        "Invalid balance in `checkEligibility` result: {balance_string:?}"
+                    ))
+                }),
+            Some(async_graphql::Value::Null) => Err(AirDropError::NotEligible),
+            _ => Err(AirDropError::MalformedOracleResponse(format!(
+                "Missing `checkEligibility` result in response data: {data_object:?}"
+            ))),
+        }
     }
 }
 
-/// An airdrop claim that has been approved and sent back to the creator chain to deliver the
-/// tokens.
+/// Creates the GraphQL variables with a list of `addresses` and the claimer's `api_token`.
+fn address_list_variables(addresses: &[Address], api_token: &str) -> async_graphql::Variables {
+    let addresses = addresses.iter().map(Address::to_string).collect::<Vec<_>>();
+
+    async_graphql::Variables::from_json(serde_json::json!({
+        "addresses": addresses,
+        "apiToken": api_token,
+    }))
+}
+
+/// Parses a [`RuleEvaluation`] returned by the service's `query`.
+fn parse_rule_evaluation(
+    value: async_graphql::Value,
+    query: &str,
+) -> Result<RuleEvaluation, AirDropError> {
+    let async_graphql::Value::Object(mut evaluation) = value else {
+        return Err(AirDropError::MalformedOracleResponse(format!(
+            "Invalid `{query}` result: {value:?}"
+        )));
+    };
+
+    match (
+        evaluation.swap_remove("satisfied"),
+        evaluation.swap_remove("explanation"),
+    ) {
+        (
+            Some(async_graphql::Value::Boolean(satisfied)),
+            Some(async_graphql::Value::String(explanation)),
+        ) => Ok(RuleEvaluation {
+            satisfied,
+            explanation,
+        }),
+        _ => Err(AirDropError::MalformedOracleResponse(format!(
+            "Invalid `{query}` result: {evaluation:?}"
+        ))),
+    }
+}
+
+/// The messages sent between the chains of the application.
+#[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(test, derive(Clone, Eq, PartialEq))]
+pub enum Message {
+    /// An airdrop claim to be paid by the receiving chain.
+    ApprovedAirDrop(ApprovedAirDrop),
+    /// A batch of airdrop claims to be paid in order by the receiving chain.
+    ApprovedAirDropBatch(Vec<ApprovedAirDrop>),
+    /// A request from the creator chain for a shard to return its surplus tokens.
+    ReturnSurplus,
+    /// A request from the creator chain for a shard to pause or resume the airdrop.
+    SetPaused { paused: bool },
+    /// A verified request from a claimer to revoke their airdrop claims with a nonce lower than
+    /// `nonce`.
+    RevokeClaims { id: AirDropId, nonce: u64 },
+}
+
+/// An airdrop claim that has been approved and sent to the payer chain to deliver the tokens.
 #[derive(Debug, Deserialize, Serialize)]
 #[cfg_attr(test, derive(Clone, Eq, PartialEq))]
 pub struct ApprovedAirDrop {
     id: AirDropId,
     amount: Amount,
     destination: Account,
+    nonce: u64,
+    relayer_fee: Option<RelayerFee>,
 }
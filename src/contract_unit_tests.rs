@@ -1,9 +1,23 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
 use airdrop_demo::{
-    test_utils::{create_dummy_application_id, create_dummy_token_id, sign_claim},
-    AirDropClaim, AirDropId, ApplicationAbi, Parameters,
+    test_utils::{
+        create_dummy_application_id, create_dummy_token_id, create_merkle_snapshot, sign_claim,
+        sign_claim_for_chain, sign_revocation, sign_sponsored_claim,
+    },
+    AirDropClaim, AirDropError, AirDropId, AirDropResponse, AllocationPolicy, AllocationTier,
+    ApplicationAbi, BalanceAggregation, ClaimReceipt, ClaimRevocation, EligibilityRule,
+    EligibilitySource, EvmChain, Operation, Parameters, PayerShard, RelayerFee, RuleEvaluation,
+    SampledBlocks, SnapshotAsset, SnapshotSampling, MAX_CLAIM_BATCH_SIZE,
 };
 use alloy_primitives::{Address, U256};
 use indexmap::IndexMap;
@@ -11,14 +25,21 @@ use k256::ecdsa::SigningKey;
 use linera_sdk::{
     abis::fungible::{self, Account, FungibleResponse},
     bcs,
-    linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, CryptoHash, Destination},
+    linera_base_types::{
+        AccountOwner, Amount, ApplicationId, BlockHeight, ChainId, CryptoHash, Destination,
+        MessageId, Timestamp,
+    },
+    serde_json,
     util::BlockingWait,
     views::View,
     Contract, ContractRuntime, Resources, SendMessageRequest,
 };
 use rand::rngs::OsRng;
 
-use super::{state::Application, ApplicationContract, ApprovedAirDrop};
+use super::{
+    state::{Application, ClaimRecord, ClaimStatus},
+    ApplicationContract, ApprovedAirDrop, Message,
+};
 
 /// Tests if a valid airdrop claim is accepted and results in a message to execute the payment.
 #[test]
@@ -27,32 +48,50 @@ fn accepts_new_claim() {
     let signing_key = SigningKey::random(&mut OsRng);
     let external_address = Address::from_private_key(&signing_key);
     let destination_account = create_dummy_destination(0);
-    let signature = sign_claim(&signing_key, application_id, destination_account);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
 
     let api_token = "API token".to_owned();
 
     contract.runtime.add_expected_service_query(
         application_id,
-        async_graphql::Request::new(format!(
-            "query {{ \
-                checkEligibility(address: \"{external_address}\", apiToken: \"{api_token}\") \
-            }}"
-        )),
+        create_eligibility_request(external_address, &api_token),
         async_graphql::Response::new(IndexMap::from_iter([(
             async_graphql::Name::new("checkEligibility"),
-            async_graphql::Value::Boolean(true),
+            async_graphql::Value::String("10".to_owned()),
         )])),
     );
 
     let claim = AirDropClaim {
         signature,
         destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
         api_token,
+        merkle_proof: None,
+        relayer_fee: None,
     };
 
-    let () = contract.execute_operation(claim).blocking_wait();
+    let response = contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
 
     let application_creator_chain_id = contract.runtime.application_creator_chain_id();
+
+    assert_eq!(
+        response,
+        AirDropResponse::Claim(ClaimReceipt {
+            claimer: external_address,
+            amount: Amount::ONE,
+            payer_chain: application_creator_chain_id,
+        })
+    );
+
     let scheduled_messages = contract.runtime.created_send_message_requests();
 
     let expected_message = SendMessageRequest {
@@ -60,131 +99,2862 @@ fn accepts_new_claim() {
         authenticated: true,
         is_tracked: false,
         grant: Resources::default(),
-        message: ApprovedAirDrop {
+        message: Message::ApprovedAirDrop(ApprovedAirDrop {
             id: external_address.into(),
             amount: Amount::ONE,
             destination: destination_account,
-        },
+            nonce: 0,
+            relayer_fee: None,
+        }),
     };
 
     assert_eq!(*scheduled_messages, vec![expected_message]);
 }
 
-/// Tests if an accepted airdrop leads to a call to transfer the tokens to the claimer.
+/// Tests if an API token crafted to inject GraphQL into the eligibility query is sent as a
+/// variable, and can't make the service report the balance of another address.
 #[test]
-fn pays_accepted_airdrop() {
-    let (mut contract, _) = create_and_instantiate_contract();
-    let airdrop_id = AirDropId::from(Address::random());
-    let amount = Amount::from_tokens(11);
-    let destination = create_dummy_destination(0);
+fn api_token_cannot_inject_into_eligibility_query() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let rich_address = Address::repeat_byte(0x01);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+    let api_token = format!(
+        "T\") @skip(if: true) ... on Query {{ \
+            checkEligibility(address: \"{rich_address}\", apiToken: \"T\") \
+        }} }} #"
+    );
 
-    let airdrop = ApprovedAirDrop {
-        id: airdrop_id,
-        amount,
-        destination,
+    struct ServiceQuery;
+
+    #[async_graphql::Object]
+    impl ServiceQuery {
+        async fn check_eligibility(
+            &self,
+            address: String,
+            #[graphql(name = "apiToken")] _api_token: String,
+        ) -> Option<String> {
+            (address == Address::repeat_byte(0x01).to_string()).then(|| "1000".to_owned())
+        }
+    }
+
+    let response = async_graphql::Schema::new(
+        ServiceQuery,
+        async_graphql::EmptyMutation,
+        async_graphql::EmptySubscription,
+    )
+    .execute(create_eligibility_request(external_address, &api_token))
+    .blocking_wait();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data,
+        async_graphql::Value::Object(IndexMap::from_iter([(
+            async_graphql::Name::new("checkEligibility"),
+            async_graphql::Value::Null,
+        )]))
+    );
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_request(external_address, &api_token),
+        response,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token,
+        merkle_proof: None,
+        relayer_fee: None,
     };
 
-    let application_id = contract.runtime.application_id();
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
 
-    contract.runtime.set_call_application_handler(
-        move |is_authenticated, target_application, operation| {
-            assert!(is_authenticated);
-            assert_eq!(target_application, create_dummy_token_id());
-            assert_eq!(
-                operation,
-                bcs::to_bytes(&fungible::Operation::Transfer {
-                    owner: AccountOwner::from(application_id),
-                    amount,
-                    target_account: destination,
-                })
-                .expect("`ApprovedAirDrop` message should be serializable")
-            );
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+    assert!(contract.runtime.created_send_message_requests().is_empty());
+}
 
-            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
-        },
+/// Tests if a claim from an address that isn't eligible is rejected.
+#[test]
+fn rejects_claim_from_ineligible_address() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let api_token = "API token".to_owned();
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_request(external_address, &api_token),
+        async_graphql::Response::new(IndexMap::from_iter([(
+            async_graphql::Name::new("checkEligibility"),
+            async_graphql::Value::Null,
+        )])),
     );
 
-    let () = contract.execute_message(airdrop).blocking_wait();
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token,
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
 }
 
-/// Tests if the same airdrop pays the claimer once.
+/// Tests if a claim is rejected when the eligibility oracle reports an error.
 #[test]
-#[should_panic(expected = "Airdrop has already been paid")]
-fn rejects_repeated_airdrop() {
-    let (mut contract, _) = create_and_instantiate_contract();
-    let airdrop_id = AirDropId::from(Address::random());
-    let amount = Amount::from_tokens(11);
-    let first_destination = create_dummy_destination(0);
-    let second_destination = create_dummy_destination(1);
+fn rejects_claim_if_oracle_fails() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
 
-    let first_claim = ApprovedAirDrop {
-        id: airdrop_id,
-        amount,
-        destination: first_destination,
+    let api_token = "API token".to_owned();
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_request(external_address, &api_token),
+        async_graphql::Response::from_errors(vec![async_graphql::ServerError::new(
+            "Failed to perform Space-and-Time query. Status-code: 401",
+            None,
+        )]),
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token,
+        merkle_proof: None,
+        relayer_fee: None,
     };
 
-    let second_claim = ApprovedAirDrop {
-        id: airdrop_id,
-        amount: Amount::ONE,
-        destination: second_destination,
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::OracleFailure(_))));
+}
+
+/// Tests if the amount of an approved claim is proportional to the snapshot balance returned by
+/// the eligibility query.
+#[test]
+fn approves_amount_proportional_to_snapshot_balance() {
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        allocation: AllocationPolicy::Proportional {
+            rate: Amount::from_tokens(3),
+            unit: U256::from(2),
+        },
+        ..create_parameters()
+    });
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let api_token = "API token".to_owned();
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_request(external_address, &api_token),
+        async_graphql::Response::new(IndexMap::from_iter([(
+            async_graphql::Name::new("checkEligibility"),
+            async_graphql::Value::String("10".to_owned()),
+        )])),
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token,
+        merkle_proof: None,
+        relayer_fee: None,
     };
 
-    let application_id = contract.runtime.application_id();
+    contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
 
-    contract.runtime.set_call_application_handler(
-        move |is_authenticated, target_application, operation| {
-            assert!(is_authenticated);
-            assert_eq!(target_application, create_dummy_token_id());
-            assert_eq!(
-                operation,
-                bcs::to_bytes(&fungible::Operation::Transfer {
-                    owner: AccountOwner::from(application_id),
-                    amount,
-                    target_account: first_destination,
-                })
-                .expect("`ApprovedAirDrop` message should be serializable")
-            );
+    let scheduled_messages = contract.runtime.created_send_message_requests();
 
-            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    assert_eq!(scheduled_messages.len(), 1);
+    assert_eq!(
+        airdrop_amount(&scheduled_messages[0].message),
+        Amount::from_tokens(15)
+    );
+}
+
+/// Tests if the amount of an approved claim is taken from the highest tier reached by the
+/// snapshot balance, limited by the cap.
+#[test]
+fn approves_capped_tiered_amounts() {
+    let small_holder_key = SigningKey::random(&mut OsRng);
+    let large_holder_key = SigningKey::random(&mut OsRng);
+    let (merkle_root, merkle_proofs) = create_merkle_snapshot(&[
+        (Address::from_private_key(&small_holder_key), U256::from(50)),
+        (
+            Address::from_private_key(&large_holder_key),
+            U256::from(500),
+        ),
+    ]);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::MerkleRoot(merkle_root),
+        allocation: AllocationPolicy::Capped {
+            policy: Box::new(AllocationPolicy::Tiered(vec![
+                AllocationTier {
+                    minimum_balance: U256::from(1),
+                    amount: Amount::from_tokens(1),
+                },
+                AllocationTier {
+                    minimum_balance: U256::from(400),
+                    amount: Amount::from_tokens(50),
+                },
+                AllocationTier {
+                    minimum_balance: U256::from(40),
+                    amount: Amount::from_tokens(5),
+                },
+            ])),
+            cap: Amount::from_tokens(20),
         },
+        ..create_parameters()
+    });
+
+    for (signing_key, merkle_proof) in [&small_holder_key, &large_holder_key]
+        .into_iter()
+        .zip(merkle_proofs)
+    {
+        let destination = create_dummy_destination(0);
+        let claim = AirDropClaim {
+            signature: sign_claim(
+                signing_key,
+                application_id,
+                destination,
+                create_deadline(),
+                0,
+            ),
+            destination,
+            deadline: create_deadline(),
+            nonce: 0,
+            api_token: String::new(),
+            merkle_proof: Some(merkle_proof),
+            relayer_fee: None,
+        };
+
+        contract
+            .execute_operation(Operation::Claim(claim))
+            .blocking_wait();
+    }
+
+    let amounts = contract
+        .runtime
+        .created_send_message_requests()
+        .iter()
+        .map(|request| airdrop_amount(&request.message))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        amounts,
+        vec![Amount::from_tokens(5), Amount::from_tokens(20)]
+    );
+}
+
+/// Tests if a claim is rejected when the allocation policy airdrops no tokens for its snapshot
+/// balance.
+#[test]
+fn rejects_claim_below_every_allocation_tier() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(30),
+        )])),
+        allocation: AllocationPolicy::Tiered(vec![AllocationTier {
+            minimum_balance: U256::from(100),
+            amount: Amount::from_tokens(5),
+        }]),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
     );
 
-    let () = contract.execute_message(first_claim).blocking_wait();
-    let () = contract.execute_message(second_claim).blocking_wait();
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ZeroAirdropAmount)));
+    assert!(contract.runtime.created_send_message_requests().is_empty());
 }
 
-/// Creates an [`ApplicationContract`] instance and calls `instantiate` on it.
-///
-/// Returns the [`ApplicationContract`] instance along with a dummy [`ApplicationId`] that was
-/// assigned to it.
-fn create_and_instantiate_contract() -> (ApplicationContract, ApplicationId<ApplicationAbi>) {
-    let application_id = create_dummy_application_id("zk-airdrop");
+/// Tests if an application can't be instantiated with an allocation policy that can't be used to
+/// calculate amounts.
+#[test]
+#[should_panic(expected = "Invalid allocation policy in application parameters")]
+fn rejects_invalid_allocation_policy() {
+    create_and_instantiate_contract_with(Parameters {
+        allocation: AllocationPolicy::Proportional {
+            rate: Amount::ONE,
+            unit: U256::ZERO,
+        },
+        ..create_parameters()
+    });
+}
 
-    let runtime = ContractRuntime::new()
-        .with_application_parameters(Parameters {
-            token_id: create_dummy_token_id(),
-            snapshot_block: 100,
-            minimum_balance: U256::from(1),
+/// Tests if an application can't be instantiated with snapshot sampling for balances that are
+/// verified by the contract.
+#[test]
+#[should_panic(expected = "Invalid snapshot sampling in application parameters")]
+fn rejects_snapshot_sampling_of_allowlist() {
+    create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::new()),
+        snapshot_sampling: Some(SnapshotSampling {
+            blocks: SampledBlocks::List(vec![50]),
+            aggregation: BalanceAggregation::Minimum,
+        }),
+        ..create_parameters()
+    });
+}
+
+/// Tests if an application can't be instantiated with a snapshot sampling range that samples too
+/// many blocks.
+#[test]
+#[should_panic(expected = "Invalid snapshot sampling in application parameters")]
+fn rejects_snapshot_sampling_with_too_many_blocks() {
+    create_and_instantiate_contract_with(Parameters {
+        snapshot_sampling: Some(SnapshotSampling {
+            blocks: SampledBlocks::Range {
+                start: 0,
+                end: 1_000,
+                step: 1,
+            },
+            aggregation: BalanceAggregation::TimeWeightedAverage,
+        }),
+        ..create_parameters()
+    });
+}
+
+/// Tests if a claim with a valid Merkle proof is accepted without querying Space-and-Time.
+#[test]
+fn accepts_claim_with_valid_merkle_proof() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (merkle_root, mut merkle_proofs) = create_merkle_snapshot(&[
+        (Address::random(), U256::from(20)),
+        (external_address, U256::from(10)),
+        (Address::random(), U256::from(30)),
+    ]);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::MerkleRoot(merkle_root),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: Some(merkle_proofs.swap_remove(1)),
+        relayer_fee: None,
+    };
+
+    let response = contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    let application_creator_chain_id = contract.runtime.application_creator_chain_id();
+
+    assert_eq!(
+        response,
+        AirDropResponse::Claim(ClaimReceipt {
+            claimer: external_address,
+            amount: Amount::ONE,
+            payer_chain: application_creator_chain_id,
         })
-        .with_application_id(application_id)
-        .with_application_creator_chain_id(ChainId(CryptoHash::test_hash("creator chain")));
+    );
 
-    let mut contract = ApplicationContract {
-        state: Application::load(runtime.root_view_storage_context())
-            .blocking_wait()
-            .expect("Failed to read from mock key value store"),
-        runtime,
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    let expected_message = SendMessageRequest {
+        destination: Destination::Recipient(application_creator_chain_id),
+        authenticated: true,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: Message::ApprovedAirDrop(ApprovedAirDrop {
+            id: external_address.into(),
+            amount: Amount::ONE,
+            destination: destination_account,
+            nonce: 0,
+            relayer_fee: None,
+        }),
     };
 
-    contract.instantiate(()).blocking_wait();
+    assert_eq!(*scheduled_messages, vec![expected_message]);
+}
 
-    (contract, application_id)
+/// Tests if a claim with a Merkle proof for a different address is rejected.
+#[test]
+fn rejects_claim_with_merkle_proof_for_other_address() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let (merkle_root, mut merkle_proofs) = create_merkle_snapshot(&[
+        (Address::random(), U256::from(20)),
+        (Address::random(), U256::from(30)),
+    ]);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::MerkleRoot(merkle_root),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: Some(merkle_proofs.swap_remove(0)),
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::InvalidMerkleProof)));
 }
 
-/// Creates a dummy [`Account`] to use as a test destination for the airdropped tokens.
-fn create_dummy_destination(index: usize) -> Account {
-    Account {
-        chain_id: ChainId(CryptoHash::test_hash(format!("destination chain {index}"))),
-        owner: AccountOwner::Address32(CryptoHash::test_hash(format!("destination owner {index}"))),
-    }
+/// Tests if a claim from an allowlisted address is accepted with its allowlisted balance.
+#[test]
+fn accepts_claim_from_allowlist() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([
+            (external_address, U256::from(30)),
+            (Address::random(), U256::from(10)),
+        ])),
+        allocation: AllocationPolicy::Proportional {
+            rate: Amount::ONE,
+            unit: U256::from(10),
+        },
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let response = contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert_eq!(
+        response,
+        AirDropResponse::Claim(ClaimReceipt {
+            claimer: external_address,
+            amount: Amount::from_tokens(3),
+            payer_chain: contract.runtime.application_creator_chain_id(),
+        })
+    );
+}
+
+/// Tests if a claim from an address that isn't in the allowlist is rejected.
+#[test]
+fn rejects_claim_outside_allowlist() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            Address::random(),
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+}
+
+/// Tests if claims are verified using the EIP-712 domain of the source chain, so that signatures
+/// for another chain don't recover the claimer's address.
+#[test]
+fn verifies_claims_signed_on_source_chain() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        source_chain: EvmChain::Base,
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+
+    let ethereum_claim = AirDropClaim {
+        signature: sign_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            create_deadline(),
+            0,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(ethereum_claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+
+    let base_claim = AirDropClaim {
+        signature: sign_claim_for_chain(
+            &signing_key,
+            EvmChain::Base,
+            application_id,
+            destination_account,
+            create_deadline(),
+            0,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let response = contract
+        .execute_operation(Operation::Claim(base_claim))
+        .blocking_wait();
+
+    assert_eq!(
+        response,
+        AirDropResponse::Claim(ClaimReceipt {
+            claimer: external_address,
+            amount: Amount::ONE,
+            payer_chain: contract.runtime.application_creator_chain_id(),
+        })
+    );
+}
+
+/// Tests if a claim is rejected after the deadline in its signature.
+#[test]
+fn rejects_expired_claim() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let deadline = Timestamp::from(1_499);
+
+    let claim = AirDropClaim {
+        signature: sign_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            deadline,
+            0,
+        ),
+        destination: destination_account,
+        deadline,
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ClaimExpired)));
+}
+
+/// Tests if the deadline of a claim can't be extended without invalidating its signature.
+#[test]
+fn rejects_claim_with_extended_deadline() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+
+    let claim = AirDropClaim {
+        signature: sign_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            Timestamp::from(1_499),
+            0,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+}
+
+/// Tests if a signed claim revocation is verified and sent to the chain that pays the claimer.
+#[test]
+fn sends_claim_revocation_to_payer_chain() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+
+    let revocation = ClaimRevocation {
+        signature: sign_revocation(&signing_key, application_id, 5),
+        nonce: 5,
+    };
+
+    let response = contract
+        .execute_operation(Operation::RevokeClaims(revocation))
+        .blocking_wait();
+
+    assert_eq!(response, AirDropResponse::Ok);
+
+    let application_creator_chain_id = contract.runtime.application_creator_chain_id();
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    let expected_message = SendMessageRequest {
+        destination: Destination::Recipient(application_creator_chain_id),
+        authenticated: true,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: Message::RevokeClaims {
+            id: external_address.into(),
+            nonce: 5,
+        },
+    };
+
+    assert_eq!(*scheduled_messages, vec![expected_message]);
+}
+
+/// Tests if a claim with a relayer fee signed by the claimer is accepted, and the fee is included
+/// in the approval sent to the payer chain.
+#[test]
+fn accepts_sponsored_claim() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        allocation: AllocationPolicy::Flat(Amount::from_tokens(10)),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let relayer_fee = RelayerFee {
+        relayer: create_dummy_destination(1),
+        amount: Amount::ONE,
+    };
+
+    let claim = AirDropClaim {
+        signature: sign_sponsored_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            create_deadline(),
+            0,
+            &relayer_fee,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: Some(relayer_fee),
+    };
+
+    let response = contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    let application_creator_chain_id = contract.runtime.application_creator_chain_id();
+
+    assert_eq!(
+        response,
+        AirDropResponse::Claim(ClaimReceipt {
+            claimer: external_address,
+            amount: Amount::from_tokens(10),
+            payer_chain: application_creator_chain_id,
+        })
+    );
+
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    assert_eq!(scheduled_messages.len(), 1);
+    assert_eq!(
+        scheduled_messages[0].message,
+        Message::ApprovedAirDrop(ApprovedAirDrop {
+            id: external_address.into(),
+            amount: Amount::from_tokens(10),
+            destination: destination_account,
+            nonce: 0,
+            relayer_fee: Some(relayer_fee),
+        })
+    );
+}
+
+/// Tests if a relayer fee that wasn't signed by the claimer can't be added to a claim, because
+/// the signature no longer recovers the claimer's address.
+#[test]
+fn rejects_claim_with_unsigned_relayer_fee() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+
+    let claim = AirDropClaim {
+        signature: sign_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            create_deadline(),
+            0,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: Some(RelayerFee {
+            relayer: create_dummy_destination(1),
+            amount: Amount::ONE,
+        }),
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+}
+
+/// Tests if a claim is rejected if its relayer fee is larger than the airdropped amount.
+#[test]
+fn rejects_relayer_fee_exceeding_airdrop() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let relayer_fee = RelayerFee {
+        relayer: create_dummy_destination(1),
+        amount: Amount::from_tokens(2),
+    };
+
+    let claim = AirDropClaim {
+        signature: sign_sponsored_claim(
+            &signing_key,
+            application_id,
+            destination_account,
+            create_deadline(),
+            0,
+            &relayer_fee,
+        ),
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: None,
+        relayer_fee: Some(relayer_fee),
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(
+        result,
+        Err(AirDropError::RelayerFeeExceedsAirdrop)
+    ));
+}
+
+/// Tests if a claim from an address that doesn't satisfy the eligibility rule is rejected with
+/// the service's explanation.
+#[test]
+fn rejects_claim_that_fails_eligibility_rule() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        rule: Some(EligibilityRule::Transactions {
+            minimum: 5,
+            before_block: 50,
+        }),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+    let api_token = "API token".to_owned();
+    let explanation = "sent 2 transactions before block 50, needed at least 5";
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_rule_request(external_address, &api_token),
+        async_graphql::Response::new(IndexMap::from_iter([(
+            async_graphql::Name::new("checkRule"),
+            async_graphql::Value::Object(IndexMap::from_iter([
+                (
+                    async_graphql::Name::new("satisfied"),
+                    async_graphql::Value::Boolean(false),
+                ),
+                (
+                    async_graphql::Name::new("explanation"),
+                    async_graphql::Value::String(explanation.to_owned()),
+                ),
+            ])),
+        )])),
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token,
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(
+        result,
+        Err(AirDropError::RuleNotSatisfied(reason)) if reason == explanation
+    ));
+}
+
+/// Tests if an API token crafted to inject GraphQL into the rule query is sent as a variable, and
+/// can't change which address the service evaluates.
+#[test]
+fn api_token_cannot_inject_into_rule_query() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let other_address = Address::repeat_byte(0x01);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from([(
+            external_address,
+            U256::from(10),
+        )])),
+        rule: Some(EligibilityRule::Transactions {
+            minimum: 5,
+            before_block: 50,
+        }),
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+    let api_token = format!(
+        "T\") @skip(if: true) {{ satisfied }} ... on Query {{ \
+            checkRule(address: \"{other_address}\", apiToken: \"T\") {{ satisfied explanation }} \
+        }} #"
+    );
+
+    struct ServiceQuery;
+
+    #[async_graphql::Object]
+    impl ServiceQuery {
+        async fn check_rule(&self, address: String, api_token: String) -> RuleEvaluation {
+            RuleEvaluation {
+                satisfied: address == Address::repeat_byte(0x01).to_string(),
+                explanation: format!("{address} with {api_token}"),
+            }
+        }
+    }
+
+    let response = async_graphql::Schema::new(
+        ServiceQuery,
+        async_graphql::EmptyMutation,
+        async_graphql::EmptySubscription,
+    )
+    .execute(create_rule_request(external_address, &api_token))
+    .blocking_wait();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let async_graphql::Value::Object(data) = &response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+    let async_graphql::Value::Object(evaluation) = &data["checkRule"] else {
+        panic!("Unexpected `checkRule` result: {data:?}");
+    };
+
+    assert_eq!(
+        evaluation["satisfied"],
+        async_graphql::Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluation["explanation"],
+        async_graphql::Value::String(format!("{external_address} with {api_token}"))
+    );
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_rule_request(external_address, &api_token),
+        response,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: api_token.clone(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::RuleNotSatisfied(_))));
+}
+
+/// Tests if a claim submitted before the claim window starts is rejected.
+#[test]
+fn rejects_claim_before_claim_window() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    contract.runtime.set_system_time(Timestamp::from(999));
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: "API token".to_owned(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ClaimWindowNotStarted)));
+}
+
+/// Tests if an approved airdrop that reaches the creator chain after the claim window ends is
+/// not paid.
+#[test]
+fn rejects_airdrop_after_claim_window() {
+    let (mut contract, _) = create_and_instantiate_contract();
+
+    contract.runtime.set_system_time(Timestamp::from(2_000));
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ClaimWindowEnded)));
+}
+
+/// Tests if an approved claim is sent to the shard responsible for the claimer's address.
+#[test]
+fn sends_approved_claim_to_payer_shard() {
+    let signing_key = SigningKey::random(&mut OsRng);
+    let external_address = Address::from_private_key(&signing_key);
+    let (merkle_root, mut merkle_proofs) =
+        create_merkle_snapshot(&[(external_address, U256::from(10))]);
+    let shards = create_dummy_shards();
+    let expected_shard = if external_address < shards[1].start {
+        &shards[0]
+    } else {
+        &shards[1]
+    };
+    let payer_chain = expected_shard.chain_id;
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::MerkleRoot(merkle_root),
+        shards,
+        ..create_parameters()
+    });
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: String::new(),
+        merkle_proof: Some(merkle_proofs.swap_remove(0)),
+        relayer_fee: None,
+    };
+
+    let response = contract
+        .execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    let AirDropResponse::Claim(receipt) = response else {
+        panic!("Unexpected response to claim: {response:?}");
+    };
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    assert_eq!(receipt.payer_chain, payer_chain);
+    assert_eq!(scheduled_messages.len(), 1);
+    assert_eq!(
+        scheduled_messages[0].destination,
+        Destination::Recipient(payer_chain)
+    );
+}
+
+/// Tests if an approved airdrop is rejected by a chain that isn't responsible for paying it.
+#[test]
+fn rejects_airdrop_on_wrong_payer_chain() {
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards: create_dummy_shards(),
+        ..create_parameters()
+    });
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::repeat_byte(0xff)),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::WrongPayerChain)));
+}
+
+/// Tests if a shard pays airdrops using its own budget.
+#[test]
+fn shard_enforces_its_own_budget() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[1].chain_id;
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+
+    contract.runtime.set_chain_id(shard_chain);
+    contract
+        .state
+        .distributed_total
+        .set(Amount::from_tokens(10));
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::repeat_byte(0xff)),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::BudgetExhausted)));
+}
+
+/// Tests if an application can't be instantiated with shards that aren't sorted.
+#[test]
+#[should_panic(expected = "Shards in application parameters must be sorted")]
+fn rejects_unsorted_shards() {
+    let mut shards = create_dummy_shards();
+    shards.reverse();
+
+    create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+}
+
+/// Tests if an application can't be instantiated with a shard paid by the creator chain.
+#[test]
+#[should_panic(expected = "Shards in application parameters can't be paid by the creator chain")]
+fn rejects_shard_on_creator_chain() {
+    let mut shards = create_dummy_shards();
+    shards[1].chain_id = ChainId(CryptoHash::test_hash("creator chain"));
+
+    create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+}
+
+/// Tests if an accepted airdrop leads to a call to transfer the tokens to the claimer.
+#[test]
+fn pays_accepted_airdrop() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let airdrop_id = AirDropId::from(Address::random());
+    let amount = Amount::from_tokens(11);
+    let destination = create_dummy_destination(0);
+
+    let airdrop = ApprovedAirDrop {
+        id: airdrop_id,
+        amount,
+        destination,
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let application_id = contract.runtime.application_id();
+
+    contract.runtime.set_call_application_handler(
+        move |is_authenticated, target_application, operation| {
+            assert!(is_authenticated);
+            assert_eq!(target_application, create_dummy_token_id());
+            assert_eq!(
+                operation,
+                bcs::to_bytes(&fungible::Operation::Transfer {
+                    owner: AccountOwner::from(application_id),
+                    amount,
+                    target_account: destination,
+                })
+                .expect("`ApprovedAirDrop` message should be serializable")
+            );
+
+            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+        },
+    );
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    let record = contract
+        .state
+        .handled_airdrops
+        .get(&airdrop_id)
+        .blocking_wait()
+        .expect("Failed to read claim history from storage");
+
+    assert_eq!(
+        record,
+        Some(ClaimRecord {
+            status: ClaimStatus::Paid,
+            amount,
+            destination,
+            block_height: BlockHeight(7),
+            timestamp: Timestamp::from(1_500),
+        })
+    );
+}
+
+/// Tests if an airdrop that exceeds the remaining budget is only paid the remainder.
+#[test]
+fn partially_pays_airdrop_exceeding_remaining_budget() {
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        budget: Amount::from_tokens(15),
+        ..create_parameters()
+    });
+    let first_destination = create_dummy_destination(0);
+    let second_destination = create_dummy_destination(1);
+
+    let first_airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(10),
+        destination: first_destination,
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let second_airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(10),
+        destination: second_destination,
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let expected_transfers = [
+        (Amount::from_tokens(10), first_destination),
+        (Amount::from_tokens(5), second_destination),
+    ];
+    let transfer_index = Arc::new(AtomicUsize::new(0));
+
+    contract.runtime.set_call_application_handler(
+        move |_is_authenticated, _target_application, operation| {
+            let (amount, target_account) =
+                expected_transfers[transfer_index.fetch_add(1, Ordering::AcqRel)];
+
+            assert_eq!(
+                operation,
+                bcs::to_bytes(&fungible::Operation::Transfer {
+                    owner: AccountOwner::from(application_id),
+                    amount,
+                    target_account,
+                })
+                .expect("`ApprovedAirDrop` message should be serializable")
+            );
+
+            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+        },
+    );
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(first_airdrop.clone()))
+        .blocking_wait();
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(second_airdrop.clone()))
+        .blocking_wait();
+
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(15)
+    );
+
+    let statuses = [first_airdrop, second_airdrop].map(|airdrop| {
+        contract
+            .state
+            .handled_airdrops
+            .get(&airdrop.id)
+            .blocking_wait()
+            .expect("Failed to read claim history from storage")
+            .map(|record| record.status)
+    });
+
+    assert_eq!(
+        statuses,
+        [Some(ClaimStatus::Paid), Some(ClaimStatus::PartiallyPaid)]
+    );
+}
+
+/// Tests if airdrops are rejected after the budget has been exhausted.
+#[test]
+fn rejects_airdrop_after_budget_is_exhausted() {
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        budget: Amount::from_tokens(10),
+        ..create_parameters()
+    });
+
+    contract
+        .state
+        .distributed_total
+        .set(Amount::from_tokens(10));
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::BudgetExhausted)));
+}
+
+/// Tests if paid airdrops are counted in the campaign statistics.
+#[test]
+fn counts_paid_airdrops_and_destination_chains() {
+    let (mut contract, _) = create_and_instantiate_contract();
+
+    contract.runtime.set_call_application_handler(|_, _, _| {
+        bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    });
+
+    for destination_index in [0, 1, 0] {
+        let airdrop = ApprovedAirDrop {
+            id: AirDropId::from(Address::random()),
+            amount: Amount::from_tokens(2),
+            destination: create_dummy_destination(destination_index),
+            nonce: 0,
+            relayer_fee: None,
+        };
+
+        let () = contract
+            .execute_message(Message::ApprovedAirDrop(airdrop))
+            .blocking_wait();
+    }
+
+    assert_eq!(*contract.state.claims_paid.get(), 3);
+    assert_eq!(*contract.state.destination_chain_count.get(), 2);
+    assert_eq!(*contract.state.rejected_replays.get(), 0);
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(6)
+    );
+}
+
+/// Tests if the same airdrop pays the claimer once.
+#[test]
+fn rejects_repeated_airdrop() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let airdrop_id = AirDropId::from(Address::random());
+    let amount = Amount::from_tokens(11);
+    let first_destination = create_dummy_destination(0);
+    let second_destination = create_dummy_destination(1);
+
+    let first_claim = ApprovedAirDrop {
+        id: airdrop_id,
+        amount,
+        destination: first_destination,
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let second_claim = ApprovedAirDrop {
+        id: airdrop_id,
+        amount: Amount::ONE,
+        destination: second_destination,
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let application_id = contract.runtime.application_id();
+
+    contract.runtime.set_call_application_handler(
+        move |is_authenticated, target_application, operation| {
+            assert!(is_authenticated);
+            assert_eq!(target_application, create_dummy_token_id());
+            assert_eq!(
+                operation,
+                bcs::to_bytes(&fungible::Operation::Transfer {
+                    owner: AccountOwner::from(application_id),
+                    amount,
+                    target_account: first_destination,
+                })
+                .expect("`ApprovedAirDrop` message should be serializable")
+            );
+
+            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+        },
+    );
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(first_claim))
+        .blocking_wait();
+
+    let result = contract.pay(second_claim.clone()).blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::AlreadyPaid)));
+    assert_eq!(*contract.state.rejected_replays.get(), 0);
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(second_claim))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 1);
+    assert_eq!(*contract.state.rejected_replays.get(), 1);
+    assert_eq!(*contract.state.distributed_total.get(), amount);
+}
+
+/// Tests if the relayer fee of an airdrop is paid to the relayer, and the rest of the airdrop to
+/// the claimer.
+#[test]
+fn pays_relayer_fee_from_airdrop() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let destination = create_dummy_destination(0);
+    let relayer = create_dummy_destination(1);
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(10),
+        destination,
+        nonce: 0,
+        relayer_fee: Some(RelayerFee {
+            relayer,
+            amount: Amount::ONE,
+        }),
+    };
+
+    let expected_transfers = [
+        (Amount::ONE, relayer),
+        (Amount::from_tokens(9), destination),
+    ];
+    let transfer_index = Arc::new(AtomicUsize::new(0));
+    let transfer_counter = transfer_index.clone();
+
+    contract.runtime.set_call_application_handler(
+        move |_is_authenticated, _target_application, operation| {
+            let (amount, target_account) =
+                expected_transfers[transfer_counter.fetch_add(1, Ordering::AcqRel)];
+
+            assert_eq!(
+                operation,
+                bcs::to_bytes(&fungible::Operation::Transfer {
+                    owner: AccountOwner::from(application_id),
+                    amount,
+                    target_account,
+                })
+                .expect("`ApprovedAirDrop` message should be serializable")
+            );
+
+            bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+        },
+    );
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert_eq!(transfer_index.load(Ordering::Acquire), 2);
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(10)
+    );
+}
+
+/// Tests if airdrops approved from claims with a revoked nonce are not paid but counted, and if
+/// revocations can't lower the minimum nonce.
+#[test]
+fn rejects_revoked_airdrop() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let airdrop_id = AirDropId::from(Address::random());
+
+    contract.runtime.set_call_application_handler(|_, _, _| {
+        bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    });
+
+    for nonce in [5, 2] {
+        let () = contract
+            .execute_message(Message::RevokeClaims {
+                id: airdrop_id,
+                nonce,
+            })
+            .blocking_wait();
+    }
+
+    let revoked_airdrop = ApprovedAirDrop {
+        id: airdrop_id,
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 4,
+        relayer_fee: None,
+    };
+
+    let result = contract.pay(revoked_airdrop.clone()).blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ClaimRevoked)));
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(revoked_airdrop))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 0);
+    assert_eq!(*contract.state.rejected_revoked_claims.get(), 1);
+
+    let valid_airdrop = ApprovedAirDrop {
+        id: airdrop_id,
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 5,
+        relayer_fee: None,
+    };
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(valid_airdrop))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 1);
+}
+
+/// Tests if a batch of valid claims is checked with a single eligibility query and results in a
+/// single message to execute all the payments.
+#[test]
+fn accepts_claim_batch() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_keys = [(); 2].map(|()| SigningKey::random(&mut OsRng));
+    let claimers = signing_keys.each_ref().map(Address::from_private_key);
+    let destinations = [0, 1].map(create_dummy_destination);
+
+    let api_token = "API token".to_owned();
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_batch_request(&claimers, &api_token),
+        create_eligibility_batch_response(&[(claimers[0], Some(10)), (claimers[1], Some(20))]),
+    );
+
+    let claims = signing_keys
+        .iter()
+        .zip(destinations)
+        .map(|(signing_key, destination)| AirDropClaim {
+            signature: sign_claim(
+                signing_key,
+                application_id,
+                destination,
+                create_deadline(),
+                0,
+            ),
+            destination,
+            deadline: create_deadline(),
+            nonce: 0,
+            api_token: api_token.clone(),
+            merkle_proof: None,
+            relayer_fee: None,
+        })
+        .collect();
+
+    let response = contract
+        .execute_operation(Operation::ClaimBatch(claims))
+        .blocking_wait();
+
+    let application_creator_chain_id = contract.runtime.application_creator_chain_id();
+
+    assert_eq!(
+        response,
+        AirDropResponse::ClaimBatch(
+            claimers
+                .iter()
+                .map(|&claimer| ClaimReceipt {
+                    claimer,
+                    amount: Amount::ONE,
+                    payer_chain: application_creator_chain_id,
+                })
+                .collect()
+        )
+    );
+
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    let expected_message = SendMessageRequest {
+        destination: Destination::Recipient(application_creator_chain_id),
+        authenticated: true,
+        is_tracked: false,
+        grant: Resources::default(),
+        message: Message::ApprovedAirDropBatch(
+            claimers
+                .iter()
+                .zip(destinations)
+                .map(|(&claimer, destination)| ApprovedAirDrop {
+                    id: claimer.into(),
+                    amount: Amount::ONE,
+                    destination,
+                    nonce: 0,
+                    relayer_fee: None,
+                })
+                .collect(),
+        ),
+    };
+
+    assert_eq!(*scheduled_messages, vec![expected_message]);
+}
+
+/// Tests if a batch of claims is rejected as a whole if one of its claimers isn't eligible.
+#[test]
+fn rejects_claim_batch_with_ineligible_address() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_keys = [(); 2].map(|()| SigningKey::random(&mut OsRng));
+    let claimers = signing_keys.each_ref().map(Address::from_private_key);
+
+    let api_token = "API token".to_owned();
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_eligibility_batch_request(&claimers, &api_token),
+        create_eligibility_batch_response(&[(claimers[0], Some(10)), (claimers[1], None)]),
+    );
+
+    let claims = signing_keys
+        .iter()
+        .enumerate()
+        .map(|(index, signing_key)| {
+            let destination = create_dummy_destination(index);
+
+            AirDropClaim {
+                signature: sign_claim(
+                    signing_key,
+                    application_id,
+                    destination,
+                    create_deadline(),
+                    0,
+                ),
+                destination,
+                deadline: create_deadline(),
+                nonce: 0,
+                api_token: api_token.clone(),
+                merkle_proof: None,
+                relayer_fee: None,
+            }
+        })
+        .collect();
+
+    let result = contract
+        .try_execute_operation(Operation::ClaimBatch(claims))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotEligible)));
+    assert!(contract.runtime.created_send_message_requests().is_empty());
+}
+
+/// Tests if the eligibility rule of all claimers in a batch is checked with a single query, and
+/// if the batch is rejected with the service's explanation when a claimer fails it.
+#[test]
+fn rejects_claim_batch_that_fails_eligibility_rule() {
+    let signing_keys = [(); 2].map(|()| SigningKey::random(&mut OsRng));
+    let claimers = signing_keys.each_ref().map(Address::from_private_key);
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        eligibility: EligibilitySource::Allowlist(BTreeMap::from(
+            claimers.map(|claimer| (claimer, U256::from(10))),
+        )),
+        rule: Some(EligibilityRule::Transactions {
+            minimum: 5,
+            before_block: 50,
+        }),
+        ..create_parameters()
+    });
+
+    let api_token = "API token".to_owned();
+    let explanation = "sent 2 transactions before block 50, needed at least 5";
+    let create_evaluation = |satisfied: bool, explanation: &str| {
+        async_graphql::Value::Object(IndexMap::from_iter([
+            (
+                async_graphql::Name::new("satisfied"),
+                async_graphql::Value::Boolean(satisfied),
+            ),
+            (
+                async_graphql::Name::new("explanation"),
+                async_graphql::Value::String(explanation.to_owned()),
+            ),
+        ]))
+    };
+
+    contract.runtime.add_expected_service_query(
+        application_id,
+        create_rule_batch_request(&claimers, &api_token),
+        async_graphql::Response::new(IndexMap::from_iter([(
+            async_graphql::Name::new("checkRuleBatch"),
+            async_graphql::Value::List(vec![
+                create_evaluation(true, "sent 9 transactions before block 50"),
+                create_evaluation(false, explanation),
+            ]),
+        )])),
+    );
+
+    let claims = signing_keys
+        .iter()
+        .enumerate()
+        .map(|(index, signing_key)| {
+            let destination = create_dummy_destination(index);
+
+            AirDropClaim {
+                signature: sign_claim(
+                    signing_key,
+                    application_id,
+                    destination,
+                    create_deadline(),
+                    0,
+                ),
+                destination,
+                deadline: create_deadline(),
+                nonce: 0,
+                api_token: api_token.clone(),
+                merkle_proof: None,
+                relayer_fee: None,
+            }
+        })
+        .collect();
+
+    let result = contract
+        .try_execute_operation(Operation::ClaimBatch(claims))
+        .blocking_wait();
+
+    assert!(matches!(
+        result,
+        Err(AirDropError::RuleNotSatisfied(reason)) if reason == explanation
+    ));
+    assert!(contract.runtime.created_send_message_requests().is_empty());
+}
+
+/// Tests if a batch with two claims from the same address is rejected.
+#[test]
+fn rejects_claim_batch_with_duplicate_claimer() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+
+    let claims = (0..2)
+        .map(|index| {
+            let destination = create_dummy_destination(index);
+
+            AirDropClaim {
+                signature: sign_claim(
+                    &signing_key,
+                    application_id,
+                    destination,
+                    create_deadline(),
+                    0,
+                ),
+                destination,
+                deadline: create_deadline(),
+                nonce: 0,
+                api_token: "API token".to_owned(),
+                merkle_proof: None,
+                relayer_fee: None,
+            }
+        })
+        .collect();
+
+    let result = contract
+        .try_execute_operation(Operation::ClaimBatch(claims))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::DuplicateClaimInBatch)));
+}
+
+/// Tests if a batch with claims paid by different shards is rejected.
+#[test]
+fn rejects_claim_batch_with_mixed_payer_chains() {
+    let signing_keys = [
+        |address: &Address| address[0] < 0x80,
+        |address: &Address| address[0] >= 0x80,
+    ]
+    .map(|is_in_shard| {
+        std::iter::repeat_with(|| SigningKey::random(&mut OsRng))
+            .find(|signing_key| is_in_shard(&Address::from_private_key(signing_key)))
+            .expect("Random keys should eventually fall in each shard")
+    });
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        shards: create_dummy_shards(),
+        ..create_parameters()
+    });
+
+    let claims = signing_keys
+        .iter()
+        .enumerate()
+        .map(|(index, signing_key)| {
+            let destination = create_dummy_destination(index);
+
+            AirDropClaim {
+                signature: sign_claim(
+                    signing_key,
+                    application_id,
+                    destination,
+                    create_deadline(),
+                    0,
+                ),
+                destination,
+                deadline: create_deadline(),
+                nonce: 0,
+                api_token: "API token".to_owned(),
+                merkle_proof: None,
+                relayer_fee: None,
+            }
+        })
+        .collect();
+
+    let result = contract
+        .try_execute_operation(Operation::ClaimBatch(claims))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::MixedPayerChains)));
+}
+
+/// Tests if an empty batch of claims is rejected.
+#[test]
+fn rejects_empty_claim_batch() {
+    let (mut contract, _) = create_and_instantiate_contract();
+
+    let result = contract
+        .try_execute_operation(Operation::ClaimBatch(vec![]))
+        .blocking_wait();
+
+    assert!(matches!(
+        result,
+        Err(AirDropError::InvalidClaimBatchSize(MAX_CLAIM_BATCH_SIZE))
+    ));
+}
+
+/// Tests if a batch of approved airdrops is paid, ignoring the airdrops that were already paid.
+#[test]
+fn pays_airdrop_batch() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let paid_airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(2),
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+    let new_airdrops = (1..3).map(|index| ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(3),
+        destination: create_dummy_destination(index),
+        nonce: 0,
+        relayer_fee: None,
+    });
+
+    contract.runtime.set_call_application_handler(|_, _, _| {
+        bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    });
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDrop(paid_airdrop.clone()))
+        .blocking_wait();
+    let () = contract
+        .execute_message(Message::ApprovedAirDropBatch(
+            new_airdrops.chain([paid_airdrop]).collect(),
+        ))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 3);
+    assert_eq!(*contract.state.rejected_replays.get(), 1);
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(8)
+    );
+}
+
+/// Tests if the airdrops of revoked claims in a batch are skipped and counted, while the rest of
+/// the batch is paid.
+#[test]
+fn skips_revoked_airdrops_in_batch() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let revoked_airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(2),
+        destination: create_dummy_destination(0),
+        nonce: 1,
+        relayer_fee: None,
+    };
+    let valid_airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::from_tokens(3),
+        destination: create_dummy_destination(1),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    contract.runtime.set_call_application_handler(|_, _, _| {
+        bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    });
+
+    let () = contract
+        .execute_message(Message::RevokeClaims {
+            id: revoked_airdrop.id,
+            nonce: 2,
+        })
+        .blocking_wait();
+    let () = contract
+        .execute_message(Message::ApprovedAirDropBatch(vec![
+            revoked_airdrop,
+            valid_airdrop,
+        ]))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 1);
+    assert_eq!(*contract.state.rejected_revoked_claims.get(), 1);
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(3)
+    );
+}
+
+/// Tests if the airdrops of a batch that exceeds the remaining budget are paid in order, until the
+/// budget is exhausted.
+#[test]
+fn partially_pays_airdrop_batch_exceeding_remaining_budget() {
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        budget: Amount::from_tokens(15),
+        ..create_parameters()
+    });
+
+    contract.runtime.set_call_application_handler(|_, _, _| {
+        bcs::to_bytes(&FungibleResponse::Ok).expect("Unit type should be serializable")
+    });
+
+    let airdrops = (0..3)
+        .map(|index| ApprovedAirDrop {
+            id: AirDropId::from(Address::random()),
+            amount: Amount::from_tokens(10),
+            destination: create_dummy_destination(index),
+            nonce: 0,
+            relayer_fee: None,
+        })
+        .collect::<Vec<_>>();
+
+    let () = contract
+        .execute_message(Message::ApprovedAirDropBatch(airdrops.clone()))
+        .blocking_wait();
+
+    assert_eq!(*contract.state.claims_paid.get(), 2);
+    assert_eq!(
+        *contract.state.distributed_total.get(),
+        Amount::from_tokens(15)
+    );
+
+    let statuses = airdrops
+        .iter()
+        .map(|airdrop| {
+            contract
+                .state
+                .handled_airdrops
+                .get(&airdrop.id)
+                .blocking_wait()
+                .expect("Failed to read claim history from storage")
+                .map(|record| record.status)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        statuses,
+        [
+            Some(ClaimStatus::Paid),
+            Some(ClaimStatus::PartiallyPaid),
+            None
+        ]
+    );
+}
+
+/// Tests if a batch of approved airdrops is rejected after the budget has been exhausted.
+#[test]
+fn rejects_airdrop_batch_after_budget_is_exhausted() {
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        budget: Amount::from_tokens(10),
+        ..create_parameters()
+    });
+
+    contract
+        .state
+        .distributed_total
+        .set(Amount::from_tokens(10));
+
+    let airdrops = (0..2)
+        .map(|index| ApprovedAirDrop {
+            id: AirDropId::from(Address::random()),
+            amount: Amount::ONE,
+            destination: create_dummy_destination(index),
+            nonce: 0,
+            relayer_fee: None,
+        })
+        .collect();
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDropBatch(airdrops))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::BudgetExhausted)));
+    assert_eq!(*contract.state.claims_paid.get(), 0);
+}
+
+/// Tests if the admin can sweep the unclaimed tokens after the claim window ends.
+#[test]
+fn admin_sweeps_unclaimed_tokens() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let target = create_dummy_destination(0);
+    let unclaimed_tokens = Amount::from_tokens(7);
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(creator_chain)
+        .set_authenticated_signer(create_admin())
+        .set_system_time(Timestamp::from(2_000));
+
+    let transfers = Arc::new(AtomicUsize::new(0));
+    let transfer_counter = transfers.clone();
+
+    contract.runtime.set_call_application_handler(
+        move |is_authenticated, target_application, operation| {
+            assert!(is_authenticated);
+            assert_eq!(target_application, create_dummy_token_id());
+
+            let owner = AccountOwner::from(application_id);
+
+            match bcs::from_bytes(&operation).expect("Failed to deserialize token operation") {
+                fungible::Operation::Balance { owner: queried } => {
+                    assert_eq!(queried, owner);
+                    bcs::to_bytes(&FungibleResponse::Balance(unclaimed_tokens))
+                }
+                _ => {
+                    assert_eq!(
+                        operation,
+                        bcs::to_bytes(&fungible::Operation::Transfer {
+                            owner,
+                            amount: unclaimed_tokens,
+                            target_account: target,
+                        })
+                        .expect("Transfer operation should be serializable")
+                    );
+                    transfer_counter.fetch_add(1, Ordering::AcqRel);
+                    bcs::to_bytes(&FungibleResponse::Ok)
+                }
+            }
+            .expect("`FungibleResponse` should be serializable")
+        },
+    );
+
+    let response = contract
+        .execute_operation(Operation::Sweep { target })
+        .blocking_wait();
+
+    assert_eq!(response, AirDropResponse::Ok);
+    assert_eq!(transfers.load(Ordering::Acquire), 1);
+}
+
+/// Tests if only the admin can sweep the unclaimed tokens.
+#[test]
+fn rejects_sweep_from_non_admin() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(creator_chain)
+        .set_authenticated_signer(AccountOwner::Address32(CryptoHash::test_hash("intruder")))
+        .set_system_time(Timestamp::from(2_000));
+
+    let result = contract
+        .try_execute_operation(Operation::Sweep {
+            target: create_dummy_destination(0),
+        })
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::Unauthorized)));
+}
+
+/// Tests if the unclaimed tokens can't be swept before the claim window ends.
+#[test]
+fn rejects_sweep_during_claim_window() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(creator_chain)
+        .set_authenticated_signer(create_admin());
+
+    let result = contract
+        .try_execute_operation(Operation::Sweep {
+            target: create_dummy_destination(0),
+        })
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::ClaimWindowStillOpen)));
+}
+
+/// Tests if the unclaimed tokens can't be swept from a chain other than the creator chain.
+#[test]
+fn rejects_sweep_outside_creator_chain() {
+    let (mut contract, _) = create_and_instantiate_contract();
+
+    contract
+        .runtime
+        .set_chain_id(ChainId(CryptoHash::test_hash("other chain")))
+        .set_authenticated_signer(create_admin())
+        .set_system_time(Timestamp::from(2_000));
+
+    let result = contract
+        .try_execute_operation(Operation::Sweep {
+            target: create_dummy_destination(0),
+        })
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::NotCreatorChain)));
+}
+
+/// Tests if the admin funds each shard with its budget, until the creator chain's tokens run out.
+#[test]
+fn admin_funds_shards() {
+    let shards = create_dummy_shards();
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        shards: shards.clone(),
+        budget: Amount::ZERO,
+        ..create_parameters()
+    });
+    let available_tokens = Amount::from_tokens(105);
+
+    contract.runtime.set_authenticated_signer(create_admin());
+
+    let transfers = Arc::new(Mutex::new(Vec::new()));
+    let transfer_log = transfers.clone();
+
+    contract.runtime.set_call_application_handler(
+        move |_is_authenticated, _target_application, operation| {
+            match bcs::from_bytes(&operation).expect("Failed to deserialize token operation") {
+                fungible::Operation::Balance { .. } => {
+                    bcs::to_bytes(&FungibleResponse::Balance(available_tokens))
+                }
+                fungible::Operation::Transfer {
+                    owner,
+                    amount,
+                    target_account,
+                } => {
+                    assert_eq!(owner, AccountOwner::from(application_id));
+                    transfer_log
+                        .lock()
+                        .expect("Transfer log should not be poisoned")
+                        .push((amount, target_account.chain_id));
+                    bcs::to_bytes(&FungibleResponse::Ok)
+                }
+                operation => panic!("Unexpected token operation: {operation:?}"),
+            }
+            .expect("`FungibleResponse` should be serializable")
+        },
+    );
+
+    let response = contract
+        .execute_operation(Operation::FundShards)
+        .blocking_wait();
+
+    assert_eq!(response, AirDropResponse::Ok);
+    assert_eq!(
+        *transfers
+            .lock()
+            .expect("Transfer log should not be poisoned"),
+        vec![
+            (Amount::from_tokens(100), shards[0].chain_id),
+            (Amount::from_tokens(5), shards[1].chain_id),
+        ]
+    );
+    assert_eq!(
+        contract
+            .state
+            .shard_funding
+            .get(&shards[1].chain_id)
+            .blocking_wait()
+            .expect("Failed to read shard funding from storage"),
+        Some(Amount::from_tokens(5))
+    );
+}
+
+/// Tests if funding the shards keeps the tokens that the creator chain needs to pay the rest of its
+/// own budget.
+#[test]
+fn funding_shards_keeps_creator_chain_budget() {
+    let upper_shard = PayerShard {
+        budget: Amount::from_tokens(100),
+        ..create_dummy_shards().swap_remove(1)
+    };
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards: vec![upper_shard.clone()],
+        budget: Amount::from_tokens(50),
+        ..create_parameters()
+    });
+    let available_tokens = Amount::from_tokens(40);
+
+    contract
+        .state
+        .distributed_total
+        .set(Amount::from_tokens(20));
+    contract.runtime.set_authenticated_signer(create_admin());
+
+    let transfers = Arc::new(Mutex::new(Vec::new()));
+    let transfer_log = transfers.clone();
+
+    contract.runtime.set_call_application_handler(
+        move |_is_authenticated, _target_application, operation| {
+            match bcs::from_bytes(&operation).expect("Failed to deserialize token operation") {
+                fungible::Operation::Balance { .. } => {
+                    bcs::to_bytes(&FungibleResponse::Balance(available_tokens))
+                }
+                fungible::Operation::Transfer {
+                    amount,
+                    target_account,
+                    ..
+                } => {
+                    transfer_log
+                        .lock()
+                        .expect("Transfer log should not be poisoned")
+                        .push((amount, target_account.chain_id));
+                    bcs::to_bytes(&FungibleResponse::Ok)
+                }
+                operation => panic!("Unexpected token operation: {operation:?}"),
+            }
+            .expect("`FungibleResponse` should be serializable")
+        },
+    );
+
+    let response = contract
+        .execute_operation(Operation::FundShards)
+        .blocking_wait();
+
+    assert_eq!(response, AirDropResponse::Ok);
+    assert_eq!(
+        *transfers
+            .lock()
+            .expect("Transfer log should not be poisoned"),
+        vec![(Amount::from_tokens(10), upper_shard.chain_id)]
+    );
+}
+
+/// Tests if the admin can request a shard to return its surplus tokens.
+#[test]
+fn admin_requests_shard_rebalance() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[0].chain_id;
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+
+    contract.runtime.set_authenticated_signer(create_admin());
+
+    let response = contract
+        .execute_operation(Operation::Rebalance { shard: shard_chain })
+        .blocking_wait();
+
+    let scheduled_messages = contract.runtime.created_send_message_requests();
+
+    assert_eq!(response, AirDropResponse::Ok);
+    assert_eq!(scheduled_messages.len(), 1);
+    assert_eq!(
+        scheduled_messages[0].destination,
+        Destination::Recipient(shard_chain)
+    );
+    assert_eq!(scheduled_messages[0].message, Message::ReturnSurplus);
+}
+
+/// Tests if a rebalance can't be requested for a chain that isn't a shard.
+#[test]
+fn rejects_rebalance_of_unknown_shard() {
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards: create_dummy_shards(),
+        ..create_parameters()
+    });
+
+    contract.runtime.set_authenticated_signer(create_admin());
+
+    let result = contract
+        .try_execute_operation(Operation::Rebalance {
+            shard: ChainId(CryptoHash::test_hash("other chain")),
+        })
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::UnknownShard)));
+}
+
+/// Tests if a shard returns the tokens it doesn't need for its remaining budget.
+#[test]
+fn shard_returns_surplus_tokens() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[1].chain_id;
+    let (mut contract, application_id) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(shard_chain)
+        .set_message_id(MessageId {
+            chain_id: creator_chain,
+            height: BlockHeight(3),
+            index: 0,
+        });
+    contract.state.distributed_total.set(Amount::from_tokens(4));
+
+    let transfers = Arc::new(AtomicUsize::new(0));
+    let transfer_counter = transfers.clone();
+
+    contract.runtime.set_call_application_handler(
+        move |_is_authenticated, _target_application, operation| {
+            match bcs::from_bytes(&operation).expect("Failed to deserialize token operation") {
+                fungible::Operation::Balance { .. } => {
+                    bcs::to_bytes(&FungibleResponse::Balance(Amount::from_tokens(10)))
+                }
+                fungible::Operation::Transfer {
+                    amount,
+                    target_account,
+                    ..
+                } => {
+                    assert_eq!(amount, Amount::from_tokens(4));
+                    assert_eq!(
+                        target_account,
+                        Account {
+                            chain_id: creator_chain,
+                            owner: AccountOwner::from(application_id),
+                        }
+                    );
+                    transfer_counter.fetch_add(1, Ordering::AcqRel);
+                    bcs::to_bytes(&FungibleResponse::Ok)
+                }
+                operation => panic!("Unexpected token operation: {operation:?}"),
+            }
+            .expect("`FungibleResponse` should be serializable")
+        },
+    );
+
+    let () = contract
+        .execute_message(Message::ReturnSurplus)
+        .blocking_wait();
+
+    assert_eq!(transfers.load(Ordering::Acquire), 1);
+}
+
+/// Tests if a shard ignores surplus requests that don't come from the creator chain.
+#[test]
+fn rejects_surplus_request_from_other_chain() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[1].chain_id;
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+
+    contract
+        .runtime
+        .set_chain_id(shard_chain)
+        .set_message_id(MessageId {
+            chain_id: ChainId(CryptoHash::test_hash("other chain")),
+            height: BlockHeight(3),
+            index: 0,
+        });
+
+    let result = contract
+        .try_execute_message(Message::ReturnSurplus)
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::UnauthorizedRebalance)));
+}
+
+/// Tests if the admin can pause and resume the airdrop.
+#[test]
+fn admin_pauses_and_resumes_airdrop() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(creator_chain)
+        .set_authenticated_signer(create_admin());
+
+    let response = contract.execute_operation(Operation::Pause).blocking_wait();
+    assert_eq!(response, AirDropResponse::Ok);
+    assert!(*contract.state.paused.get());
+
+    let response = contract
+        .execute_operation(Operation::Resume)
+        .blocking_wait();
+    assert_eq!(response, AirDropResponse::Ok);
+    assert!(!*contract.state.paused.get());
+}
+
+/// Tests if pausing and resuming the airdrop is sent to every shard.
+#[test]
+fn admin_pauses_and_resumes_shards() {
+    let shards = create_dummy_shards();
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards: shards.clone(),
+        ..create_parameters()
+    });
+
+    contract.runtime.set_authenticated_signer(create_admin());
+
+    for (operation, paused) in [(Operation::Pause, true), (Operation::Resume, false)] {
+        let response = contract.execute_operation(operation).blocking_wait();
+        assert_eq!(response, AirDropResponse::Ok);
+
+        let expected_messages = shards
+            .iter()
+            .map(|shard| SendMessageRequest {
+                destination: Destination::Recipient(shard.chain_id),
+                authenticated: true,
+                is_tracked: false,
+                grant: Resources::default(),
+                message: Message::SetPaused { paused },
+            })
+            .collect::<Vec<_>>();
+
+        let mut scheduled_messages = contract.runtime.created_send_message_requests();
+        assert_eq!(*scheduled_messages, expected_messages);
+        scheduled_messages.clear();
+    }
+}
+
+/// Tests if a shard stops paying airdrops after the creator chain pauses it.
+#[test]
+fn shard_rejects_airdrop_while_paused() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[1].chain_id;
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(shard_chain)
+        .set_message_id(MessageId {
+            chain_id: creator_chain,
+            height: BlockHeight(3),
+            index: 0,
+        });
+
+    let () = contract
+        .execute_message(Message::SetPaused { paused: true })
+        .blocking_wait();
+
+    let mut address = [0xff_u8; 20];
+    address[19] = 0;
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::from(address)),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::Paused)));
+}
+
+/// Tests if a shard ignores pause requests that don't come from the creator chain.
+#[test]
+fn rejects_pause_request_from_other_chain() {
+    let shards = create_dummy_shards();
+    let shard_chain = shards[1].chain_id;
+    let (mut contract, _) = create_and_instantiate_contract_with(Parameters {
+        shards,
+        ..create_parameters()
+    });
+
+    contract
+        .runtime
+        .set_chain_id(shard_chain)
+        .set_message_id(MessageId {
+            chain_id: ChainId(CryptoHash::test_hash("other chain")),
+            height: BlockHeight(3),
+            index: 0,
+        });
+
+    let result = contract
+        .try_execute_message(Message::SetPaused { paused: true })
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::UnauthorizedPause)));
+    assert!(!*contract.state.paused.get());
+}
+
+/// Tests if only the admin can pause the airdrop.
+#[test]
+fn rejects_pause_from_non_admin() {
+    let (mut contract, _) = create_and_instantiate_contract();
+    let creator_chain = contract.runtime.application_creator_chain_id();
+
+    contract
+        .runtime
+        .set_chain_id(creator_chain)
+        .set_authenticated_signer(AccountOwner::Address32(CryptoHash::test_hash("intruder")));
+
+    let result = contract
+        .try_execute_operation(Operation::Pause)
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::Unauthorized)));
+}
+
+/// Tests if claims are rejected while the airdrop is paused.
+#[test]
+fn rejects_claim_while_paused() {
+    let (mut contract, application_id) = create_and_instantiate_contract();
+    let signing_key = SigningKey::random(&mut OsRng);
+    let destination_account = create_dummy_destination(0);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination_account,
+        create_deadline(),
+        0,
+    );
+
+    contract.state.paused.set(true);
+
+    let claim = AirDropClaim {
+        signature,
+        destination: destination_account,
+        deadline: create_deadline(),
+        nonce: 0,
+        api_token: "API token".to_owned(),
+        merkle_proof: None,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_operation(Operation::Claim(claim))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::Paused)));
+}
+
+/// Tests if approved airdrops are not paid while the airdrop is paused.
+#[test]
+fn rejects_airdrop_while_paused() {
+    let (mut contract, _) = create_and_instantiate_contract();
+
+    contract.state.paused.set(true);
+
+    let airdrop = ApprovedAirDrop {
+        id: AirDropId::from(Address::random()),
+        amount: Amount::ONE,
+        destination: create_dummy_destination(0),
+        nonce: 0,
+        relayer_fee: None,
+    };
+
+    let result = contract
+        .try_execute_message(Message::ApprovedAirDrop(airdrop))
+        .blocking_wait();
+
+    assert!(matches!(result, Err(AirDropError::Paused)));
+}
+
+/// Creates an [`ApplicationContract`] instance and calls `instantiate` on it.
+///
+/// Returns the [`ApplicationContract`] instance along with a dummy [`ApplicationId`] that was
+/// assigned to it.
+fn create_and_instantiate_contract() -> (ApplicationContract, ApplicationId<ApplicationAbi>) {
+    create_and_instantiate_contract_with(create_parameters())
+}
+
+/// Creates an [`ApplicationContract`] instance with the specified [`Parameters`] and calls
+/// `instantiate` on it.
+///
+/// Returns the [`ApplicationContract`] instance along with a dummy [`ApplicationId`] that was
+/// assigned to it.
+fn create_and_instantiate_contract_with(
+    parameters: Parameters,
+) -> (ApplicationContract, ApplicationId<ApplicationAbi>) {
+    let application_id = create_dummy_application_id("zk-airdrop");
+
+    let creator_chain = ChainId(CryptoHash::test_hash("creator chain"));
+    let runtime = ContractRuntime::new()
+        .with_application_parameters(parameters)
+        .with_application_id(application_id)
+        .with_application_creator_chain_id(creator_chain)
+        .with_chain_id(creator_chain)
+        .with_system_time(Timestamp::from(1_500))
+        .with_block_height(BlockHeight(7));
+
+    let mut contract = ApplicationContract {
+        state: Application::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to read from mock key value store"),
+        runtime,
+    };
+
+    contract.instantiate(()).blocking_wait();
+
+    (contract, application_id)
+}
+
+/// Creates the request sent to the service for the snapshot balance of an `address`.
+fn create_eligibility_request(address: Address, api_token: &str) -> async_graphql::Request {
+    async_graphql::Request::new(super::CHECK_ELIGIBILITY_QUERY).variables(
+        async_graphql::Variables::from_json(serde_json::json!({
+            "address": address.to_string(),
+            "apiToken": api_token,
+        })),
+    )
+}
+
+/// Creates the request sent to the service to evaluate the eligibility rule for an `address`.
+fn create_rule_request(address: Address, api_token: &str) -> async_graphql::Request {
+    async_graphql::Request::new(super::CHECK_RULE_QUERY).variables(
+        async_graphql::Variables::from_json(serde_json::json!({
+            "address": address.to_string(),
+            "apiToken": api_token,
+        })),
+    )
+}
+
+/// Creates the request sent to the service to evaluate the eligibility rule for several
+/// `addresses`.
+fn create_rule_batch_request(addresses: &[Address], api_token: &str) -> async_graphql::Request {
+    async_graphql::Request::new(super::CHECK_RULE_BATCH_QUERY).variables(
+        async_graphql::Variables::from_json(serde_json::json!({
+            "addresses": addresses.iter().map(Address::to_string).collect::<Vec<_>>(),
+            "apiToken": api_token,
+        })),
+    )
+}
+
+/// Creates the request sent to the service for the snapshot balances of several `addresses`.
+fn create_eligibility_batch_request(
+    addresses: &[Address],
+    api_token: &str,
+) -> async_graphql::Request {
+    async_graphql::Request::new(super::CHECK_ELIGIBILITY_BATCH_QUERY).variables(
+        async_graphql::Variables::from_json(serde_json::json!({
+            "addresses": addresses.iter().map(Address::to_string).collect::<Vec<_>>(),
+            "apiToken": api_token,
+        })),
+    )
+}
+
+/// Creates the default [`Parameters`] used to instantiate the application in the tests.
+fn create_parameters() -> Parameters {
+    Parameters {
+        token_id: create_dummy_token_id(),
+        source_chain: EvmChain::Ethereum,
+        snapshot_block: 100,
+        snapshot_sampling: None,
+        minimum_balance: U256::from(1),
+        asset: SnapshotAsset::Native,
+        eligibility: EligibilitySource::SpaceAndTime,
+        rule: None,
+        allocation: AllocationPolicy::Flat(Amount::ONE),
+        budget: Amount::from_tokens(1_000),
+        claim_start: Timestamp::from(1_000),
+        claim_end: Timestamp::from(2_000),
+        admin: create_admin(),
+        shards: vec![],
+    }
+}
+
+/// Creates two [`PayerShard`]s that split the address space in half.
+fn create_dummy_shards() -> Vec<PayerShard> {
+    let mut middle_address = [0_u8; 20];
+    middle_address[0] = 0x80;
+
+    vec![
+        PayerShard {
+            start: Address::ZERO,
+            chain_id: ChainId(CryptoHash::test_hash("lower shard")),
+            budget: Amount::from_tokens(100),
+        },
+        PayerShard {
+            start: Address::from(middle_address),
+            chain_id: ChainId(CryptoHash::test_hash("upper shard")),
+            budget: Amount::from_tokens(10),
+        },
+    ]
+}
+
+/// Creates the deadline of the claims signed in the tests, which is the end of the claim window.
+fn create_deadline() -> Timestamp {
+    Timestamp::from(2_000)
+}
+
+/// Creates the [`AccountOwner`] used as the application's admin in the tests.
+fn create_admin() -> AccountOwner {
+    AccountOwner::Address32(CryptoHash::test_hash("admin"))
+}
+
+/// Creates a dummy [`Account`] to use as a test destination for the airdropped tokens.
+fn create_dummy_destination(index: usize) -> Account {
+    Account {
+        chain_id: ChainId(CryptoHash::test_hash(format!("destination chain {index}"))),
+        owner: AccountOwner::Address32(CryptoHash::test_hash(format!("destination owner {index}"))),
+    }
+}
+
+/// Creates the service's response to a `checkEligibilityBatch` query with the snapshot
+/// `balances` of each address.
+fn create_eligibility_batch_response(
+    balances: &[(Address, Option<u64>)],
+) -> async_graphql::Response {
+    let entries = balances
+        .iter()
+        .map(|(address, balance)| {
+            async_graphql::Value::Object(IndexMap::from_iter([
+                (
+                    async_graphql::Name::new("address"),
+                    async_graphql::Value::String(address.to_string()),
+                ),
+                (
+                    async_graphql::Name::new("balance"),
+                    balance.map_or(async_graphql::Value::Null, |balance| {
+                        async_graphql::Value::String(balance.to_string())
+                    }),
+                ),
+            ]))
+        })
+        .collect();
+
+    async_graphql::Response::new(IndexMap::from_iter([(
+        async_graphql::Name::new("checkEligibilityBatch"),
+        async_graphql::Value::List(entries),
+    )]))
+}
+
+/// Returns the amount of tokens in a [`Message::ApprovedAirDrop`].
+fn airdrop_amount(message: &Message) -> Amount {
+    let Message::ApprovedAirDrop(airdrop) = message else {
+        panic!("Unexpected message: {message:?}");
+    };
+
+    airdrop.amount
 }
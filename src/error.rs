@@ -0,0 +1,110 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The reasons why the application may reject an operation or a message.
+
+use alloy_primitives::SignatureError;
+use thiserror::Error;
+
+/// An error that rejects an airdrop operation or message.
+#[derive(Debug, Error)]
+pub enum AirDropError {
+    #[error("Invalid allocation policy in application parameters")]
+    InvalidAllocationPolicy,
+
+    #[error("Claim window in application parameters must start before it ends")]
+    InvalidClaimWindow,
+
+    #[error("Shards in application parameters must be sorted and have distinct chains")]
+    InvalidShardMap,
+
+    #[error("Shards in application parameters can't be paid by the creator chain")]
+    ShardOnCreatorChain,
+
+    #[error("Invalid snapshot sampling in application parameters")]
+    InvalidSnapshotSampling,
+
+    #[error("Failed to verify signature: {0}")]
+    InvalidSignature(#[from] SignatureError),
+
+    #[error("Claim batch must have between 1 and {0} claims")]
+    InvalidClaimBatchSize(usize),
+
+    #[error("Claim batch has more than one claim from the same address")]
+    DuplicateClaimInBatch,
+
+    #[error("Claims in a batch must all be paid by the same chain")]
+    MixedPayerChains,
+
+    #[error("Relayer fee is larger than the airdropped amount")]
+    RelayerFeeExceedsAirdrop,
+
+    #[error("Airdrop claim signature has expired")]
+    ClaimExpired,
+
+    #[error("Airdrop claim has been revoked by the claimer")]
+    ClaimRevoked,
+
+    #[error("Address is not eligible for the airdrop")]
+    NotEligible,
+
+    #[error("Missing Merkle proof in airdrop claim")]
+    MissingMerkleProof,
+
+    #[error("Invalid Merkle proof for airdrop claim")]
+    InvalidMerkleProof,
+
+    #[error("Snapshot balance is below the minimum balance")]
+    InsufficientBalance,
+
+    #[error("Allocation policy airdrops no tokens for the snapshot balance")]
+    ZeroAirdropAmount,
+
+    #[error("Eligibility rule is not satisfied: {0}")]
+    RuleNotSatisfied(String),
+
+    #[error("Eligibility oracle failed: {0}")]
+    OracleFailure(String),
+
+    #[error("Malformed response from eligibility oracle: {0}")]
+    MalformedOracleResponse(String),
+
+    #[error("Airdrop is paused")]
+    Paused,
+
+    #[error("Airdrop claim window has not started yet")]
+    ClaimWindowNotStarted,
+
+    #[error("Airdrop claim window has already ended")]
+    ClaimWindowEnded,
+
+    #[error("Airdrop has already been paid")]
+    AlreadyPaid,
+
+    #[error("Airdrop budget has been exhausted")]
+    BudgetExhausted,
+
+    #[error("Airdrop must be paid by the chain of its shard")]
+    WrongPayerChain,
+
+    #[error("Admin operations can only be executed on the creator chain")]
+    NotCreatorChain,
+
+    #[error("Admin operations can only be executed by the admin")]
+    Unauthorized,
+
+    #[error("Unclaimed tokens can only be swept after the claim window ends")]
+    ClaimWindowStillOpen,
+
+    #[error("Chain is not a shard of the airdrop")]
+    UnknownShard,
+
+    #[error("Only the creator chain can request a shard to rebalance its tokens")]
+    UnauthorizedRebalance,
+
+    #[error("Only the creator chain can pause or resume a shard")]
+    UnauthorizedPause,
+
+    #[error("Unexpected response from token application")]
+    UnexpectedTokenResponse,
+}
@@ -0,0 +1,48 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The EVM chains whose snapshots can be used to decide who is eligible for the airdrop.
+
+use alloy_primitives::aliases::ChainId;
+use serde::{Deserialize, Serialize};
+
+/// The EVM chain where the snapshot is taken and whose accounts sign the claims.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EvmChain {
+    /// The Ethereum mainnet.
+    Ethereum,
+    /// The Polygon PoS mainnet.
+    Polygon,
+    /// The Arbitrum One mainnet.
+    Arbitrum,
+    /// The Base mainnet.
+    Base,
+    /// The OP mainnet.
+    Optimism,
+}
+
+impl EvmChain {
+    /// Returns the [EIP-155] chain ID of this chain.
+    ///
+    /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+    pub const fn chain_id(&self) -> ChainId {
+        match self {
+            EvmChain::Ethereum => 1,
+            EvmChain::Polygon => 137,
+            EvmChain::Arbitrum => 42_161,
+            EvmChain::Base => 8_453,
+            EvmChain::Optimism => 10,
+        }
+    }
+
+    /// Returns the Space-and-Time schema with the tables of this chain.
+    pub(crate) fn sxt_schema(&self) -> &'static str {
+        match self {
+            EvmChain::Ethereum => "ETHEREUM",
+            EvmChain::Polygon => "POLYGON",
+            EvmChain::Arbitrum => "ARBITRUM",
+            EvmChain::Base => "BASE",
+            EvmChain::Optimism => "OPTIMISM",
+        }
+    }
+}
@@ -1,28 +1,59 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod allocation;
+mod asset;
+mod error;
+mod evm_chain;
+mod merkle;
+mod normalization;
+mod oracle;
+mod rules;
+mod sharding;
 pub(crate) mod signature_payload;
+mod snapshot;
+mod sql;
 #[cfg(feature = "test")]
 pub mod test_utils;
 
 use std::str::FromStr;
 
-use alloy_primitives::{Address, PrimitiveSignature, SignatureError, U256};
-use alloy_sol_types::SolStruct;
+use alloy_primitives::{Address, PrimitiveSignature, U256};
 use indexmap::IndexMap;
 use linera_sdk::{
     abis::fungible::{Account, FungibleTokenAbi},
-    linera_base_types::{ApplicationId, ContractAbi, ServiceAbi},
+    linera_base_types::{
+        AccountOwner, Amount, ApplicationId, ChainId, ContractAbi, ServiceAbi, Timestamp,
+    },
 };
 use serde::{Deserialize, Serialize};
 
-use self::signature_payload::AIRDROP_CLAIM_DOMAIN;
+pub use self::allocation::{AllocationPolicy, AllocationTier};
+pub use self::asset::SnapshotAsset;
+pub use self::error::AirDropError;
+pub use self::evm_chain::EvmChain;
+pub use self::merkle::{merkle_leaf, merkle_node, MerkleProof};
+pub use self::normalization::{
+    normalize_numeric, normalize_snapshot_balance, normalize_wallet_balances, SnapshotBalance,
+    WalletBalance,
+};
+pub use self::oracle::{
+    AllowlistOracle, EligibilityOracle, EligibilitySource, JsonRpcOracle, MerkleOracle,
+    SpaceAndTimeOracle, MAX_ADDRESSES_PER_QUERY, SXT_GATEWAY_URL,
+};
+pub use self::rules::{EligibilityRule, RuleEvaluation, RuleOracle};
+pub use self::sharding::PayerShard;
+use self::signature_payload::{claim_signing_hash, revocation_signing_hash};
+pub use self::snapshot::{
+    BalanceAggregation, SampledBlocks, SnapshotSampling, MAX_SNAPSHOT_SAMPLES,
+};
+pub use self::sql::{Comparison, SelectQuery, SqlValue};
 
 pub struct ApplicationAbi;
 
 impl ContractAbi for ApplicationAbi {
-    type Operation = AirDropClaim;
-    type Response = ();
+    type Operation = Operation;
+    type Response = AirDropResponse;
 }
 
 impl ServiceAbi for ApplicationAbi {
@@ -30,12 +61,157 @@ impl ServiceAbi for ApplicationAbi {
     type QueryResponse = async_graphql::Response;
 }
 
+/// The maximum number of claims in an [`Operation::ClaimBatch`].
+pub const MAX_CLAIM_BATCH_SIZE: usize = 100;
+
+/// The operations supported by the application.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum Operation {
+    /// Claims an airdrop.
+    Claim(AirDropClaim),
+    /// Claims the airdrops of several claimers at once, sending a single approval to the chain
+    /// that pays them.
+    ///
+    /// All claims must be valid and paid by the same chain, otherwise the whole batch is
+    /// rejected. At most [`MAX_CLAIM_BATCH_SIZE`] claims can be included in a batch.
+    ClaimBatch(Vec<AirDropClaim>),
+    /// Transfers the tokens that were not claimed to a `target` account.
+    ///
+    /// Can only be executed by the admin on the creator chain, after the claim window ends.
+    Sweep { target: Account },
+    /// Pauses the airdrop, rejecting all claims until it is resumed.
+    ///
+    /// Can only be executed by the admin on the creator chain.
+    Pause,
+    /// Resumes a paused airdrop.
+    ///
+    /// Can only be executed by the admin on the creator chain.
+    Resume,
+    /// Transfers tokens from the creator chain to each [`PayerShard`], until each shard has been
+    /// funded with its budget or the creator chain's tokens run out.
+    ///
+    /// Can only be executed by the admin on the creator chain.
+    FundShards,
+    /// Requests the `shard` to return the tokens that it doesn't need to pay its remaining budget
+    /// to the creator chain.
+    ///
+    /// Can only be executed by the admin on the creator chain.
+    Rebalance { shard: ChainId },
+    /// Revokes the claimer's signed [`AirDropClaim`]s with a nonce lower than the one in the
+    /// [`ClaimRevocation`], so that they are no longer paid.
+    RevokeClaims(ClaimRevocation),
+}
+
+/// The response to an [`Operation`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum AirDropResponse {
+    /// The operation was executed and has nothing to report.
+    Ok,
+    /// The [`AirDropClaim`] was approved and sent to be paid.
+    Claim(ClaimReceipt),
+    /// All the [`AirDropClaim`]s in a batch were approved and sent to be paid, in the same order
+    /// as in the batch.
+    ClaimBatch(Vec<ClaimReceipt>),
+}
+
+/// The receipt of an approved [`AirDropClaim`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClaimReceipt {
+    /// The Ethereum [`Address`] that signed the claim.
+    pub claimer: Address,
+    /// The [`Amount`] of tokens approved to be paid.
+    pub amount: Amount,
+    /// The chain that the approval was sent to, which will deliver the tokens.
+    pub payer_chain: ChainId,
+}
+
 /// The shared parameters that are specified when the application is instantiated.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Parameters {
     pub token_id: ApplicationId<FungibleTokenAbi>,
+    /// The EVM chain where the snapshot is taken and whose accounts sign the claims.
+    pub source_chain: EvmChain,
     pub snapshot_block: u64,
+    /// Additional blocks at which the service samples the balances of claimers, and how the
+    /// samples are combined before comparing them to the `minimum_balance`.
+    ///
+    /// Can only be used with the [`EligibilitySource`]s queried by the service.
+    pub snapshot_sampling: Option<SnapshotSampling>,
     pub minimum_balance: U256,
+    /// The asset whose balance at the snapshot block decides if a claimer is eligible.
+    pub asset: SnapshotAsset,
+    /// The source of the snapshot balances used to verify if claimers are eligible.
+    pub eligibility: EligibilitySource,
+    /// An additional rule that claimers must satisfy, which is evaluated by the service.
+    ///
+    /// The service queries the rule's criteria from the JSON-RPC endpoint if it is the
+    /// [`EligibilitySource`], or from Space-and-Time otherwise.
+    pub rule: Option<EligibilityRule>,
+    /// The policy used to calculate how many tokens each claimer receives.
+    pub allocation: AllocationPolicy,
+    /// The maximum total [`Amount`] of tokens distributed by the creator chain.
+    ///
+    /// Claims paid after the budget is exhausted are rejected, and the last claim paid may only
+    /// receive the remainder of the budget. Each [`PayerShard`] has its own budget.
+    pub budget: Amount,
+    /// The earliest block [`Timestamp`] at which claims are accepted.
+    pub claim_start: Timestamp,
+    /// The block [`Timestamp`] from which claims are no longer accepted.
+    pub claim_end: Timestamp,
+    /// The owner allowed to execute admin operations.
+    pub admin: AccountOwner,
+    /// The shards that pay the airdrops of ranges of claimer addresses, sorted by the start of
+    /// their ranges.
+    ///
+    /// Airdrops of addresses before the first shard's range are paid by the creator chain.
+    pub shards: Vec<PayerShard>,
+}
+
+impl Parameters {
+    /// Checks that the [`Parameters`] can be used to run an airdrop.
+    pub fn validate(&self) -> Result<(), AirDropError> {
+        if !self.allocation.is_valid() {
+            return Err(AirDropError::InvalidAllocationPolicy);
+        }
+
+        if self.claim_start >= self.claim_end {
+            return Err(AirDropError::InvalidClaimWindow);
+        }
+
+        if !sharding::is_valid_shard_map(&self.shards) {
+            return Err(AirDropError::InvalidShardMap);
+        }
+
+        if let Some(sampling) = &self.snapshot_sampling {
+            let is_queried_by_service = matches!(
+                self.eligibility,
+                EligibilitySource::SpaceAndTime | EligibilitySource::JsonRpc { .. }
+            );
+
+            if !is_queried_by_service || sampling.blocks(self.snapshot_block).is_none() {
+                return Err(AirDropError::InvalidSnapshotSampling);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the chain responsible for paying the airdrop of an `address`, or [`None`] if it
+    /// should be paid by the creator chain.
+    pub fn payer_chain(&self, address: &Address) -> Option<ChainId> {
+        sharding::find_payer_shard(&self.shards, address).map(|shard| shard.chain_id)
+    }
+
+    /// Returns the budget of the payer chain with the specified [`ChainId`].
+    ///
+    /// Chains that aren't shards use the budget of the creator chain.
+    pub fn payer_budget(&self, chain_id: ChainId) -> Amount {
+        self.shards
+            .iter()
+            .find(|shard| shard.chain_id == chain_id)
+            .map_or(self.budget, |shard| shard.budget)
+    }
 }
 
 /// The information necessary to identify an airdrop.
@@ -44,6 +220,13 @@ pub struct AirDropId {
     external_address: Address,
 }
 
+impl AirDropId {
+    /// Returns the Ethereum [`Address`] of the claimer.
+    pub fn external_address(&self) -> Address {
+        self.external_address
+    }
+}
+
 impl From<Address> for AirDropId {
     fn from(external_address: Address) -> Self {
         AirDropId { external_address }
@@ -86,9 +269,7 @@ impl async_graphql::ScalarType for AirDropId {
     }
 
     fn to_value(&self) -> async_graphql::Value {
-        self.external_address
-            .to_checksum(Some(ETHEREUM_MAINNET_CHAIN_ID))
-            .into()
+        self.external_address.to_checksum(None).into()
     }
 }
 
@@ -97,20 +278,67 @@ impl async_graphql::ScalarType for AirDropId {
 pub struct AirDropClaim {
     pub signature: PrimitiveSignature,
     pub destination: Account,
+    /// The last [`Timestamp`] at which the signed claim is accepted.
+    pub deadline: Timestamp,
+    /// A number chosen by the claimer, which allows revoking the signed claim with a
+    /// [`ClaimRevocation`].
+    pub nonce: u64,
     pub api_token: String,
+    pub merkle_proof: Option<MerkleProof>,
+    /// The fee paid to the relayer that submits the claim on the claimer's behalf, which is
+    /// included in the signed payload.
+    pub relayer_fee: Option<RelayerFee>,
+}
+
+/// A fee deducted from an airdrop and paid to the relayer that submitted its claim.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, async_graphql::InputObject)]
+pub struct RelayerFee {
+    /// The account that receives the fee.
+    pub relayer: Account,
+    /// The [`Amount`] of airdropped tokens paid to the relayer.
+    pub amount: Amount,
 }
 
 impl AirDropClaim {
-    /// Returns the signer's Ethereum [`Address`] for this [`AirDropClaim`].
+    /// Returns the signer's Ethereum [`Address`] for this [`AirDropClaim`], signed on the
+    /// `source_chain`.
     pub fn signer_address(
         &self,
         application_id: ApplicationId<ApplicationAbi>,
-    ) -> Result<Address, SignatureError> {
-        let payload = signature_payload::AirDropClaim::new(application_id, &self.destination);
+        source_chain: EvmChain,
+    ) -> Result<Address, AirDropError> {
+        let hash = claim_signing_hash(
+            application_id,
+            &self.destination,
+            self.deadline,
+            self.nonce,
+            self.relayer_fee.as_ref(),
+            source_chain,
+        );
+
+        Ok(self.signature.recover_address_from_prehash(&hash)?)
+    }
+}
+
+/// A request signed by a claimer to revoke their [`AirDropClaim`]s with a nonce lower than
+/// `nonce`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClaimRevocation {
+    pub signature: PrimitiveSignature,
+    pub nonce: u64,
+}
 
-        let hash = payload.eip712_signing_hash(&AIRDROP_CLAIM_DOMAIN);
+impl ClaimRevocation {
+    /// Returns the signer's Ethereum [`Address`] for this [`ClaimRevocation`], signed on the
+    /// `source_chain`.
+    pub fn signer_address(
+        &self,
+        application_id: ApplicationId<ApplicationAbi>,
+        source_chain: EvmChain,
+    ) -> Result<Address, AirDropError> {
+        let hash = revocation_signing_hash(application_id, self.nonce, source_chain);
 
-        self.signature.recover_address_from_prehash(&hash)
+        Ok(self.signature.recover_address_from_prehash(&hash)?)
     }
 }
 
@@ -121,13 +349,6 @@ impl async_graphql::ScalarType for AirDropClaim {
             return Err(async_graphql::InputValueError::expected_type(value));
         };
 
-        if fields.len() != 2 {
-            return Err(async_graphql::InputValueError::custom(
-                "`AirDropClaim` object must have exactly three fields: \
-                `signature`, `destination` and `apiToken`",
-            ));
-        }
-
         let Some(signature_value) = fields.swap_remove("signature") else {
             return Err(async_graphql::InputValueError::custom(
                 "`AirDropClaim` object is missing an `signature` field",
@@ -158,9 +379,31 @@ impl async_graphql::ScalarType for AirDropClaim {
                 Err(error) => return Err(error.propagate()),
             };
 
+        let Some(deadline_value) = fields.swap_remove("deadline") else {
+            return Err(async_graphql::InputValueError::custom(
+                "`AirDropClaim` object is missing a `deadline` field",
+            ));
+        };
+
+        let deadline = match <Timestamp as async_graphql::InputType>::parse(Some(deadline_value)) {
+            Ok(deadline) => deadline,
+            Err(error) => return Err(error.propagate()),
+        };
+
+        let Some(nonce_value) = fields.swap_remove("nonce") else {
+            return Err(async_graphql::InputValueError::custom(
+                "`AirDropClaim` object is missing a `nonce` field",
+            ));
+        };
+
+        let nonce = match <u64 as async_graphql::InputType>::parse(Some(nonce_value)) {
+            Ok(nonce) => nonce,
+            Err(error) => return Err(error.propagate()),
+        };
+
         let Some(api_token_value) = fields.swap_remove("apiToken") else {
             return Err(async_graphql::InputValueError::custom(
-                "`AirDropClaim` object is missing an `signature` field",
+                "`AirDropClaim` object is missing an `apiToken` field",
             ));
         };
 
@@ -170,10 +413,42 @@ impl async_graphql::ScalarType for AirDropClaim {
             ));
         };
 
+        let merkle_proof = match fields.swap_remove("merkleProof") {
+            None | Some(async_graphql::Value::Null) => None,
+            Some(merkle_proof_value) => {
+                match <MerkleProof as async_graphql::ScalarType>::parse(merkle_proof_value) {
+                    Ok(merkle_proof) => Some(merkle_proof),
+                    Err(error) => return Err(error.propagate()),
+                }
+            }
+        };
+
+        let relayer_fee = match fields.swap_remove("relayerFee") {
+            None | Some(async_graphql::Value::Null) => None,
+            Some(relayer_fee_value) => {
+                match <RelayerFee as async_graphql::InputType>::parse(Some(relayer_fee_value)) {
+                    Ok(relayer_fee) => Some(relayer_fee),
+                    Err(error) => return Err(error.propagate()),
+                }
+            }
+        };
+
+        if let Some(unknown_field) = fields.keys().next() {
+            return Err(async_graphql::InputValueError::custom(format!(
+                "`AirDropClaim` object has an unknown field `{unknown_field}`, it must only have \
+                the `signature`, `destination`, `deadline`, `nonce` and `apiToken` fields, \
+                and optionally a `merkleProof` and a `relayerFee`"
+            )));
+        }
+
         Ok(AirDropClaim {
             signature,
             destination,
+            deadline,
+            nonce,
             api_token,
+            merkle_proof,
+            relayer_fee,
         })
     }
 
@@ -186,6 +461,28 @@ impl async_graphql::ScalarType for AirDropClaim {
 
         fields.insert(async_graphql::Name::new("signature"), signature);
         fields.insert(async_graphql::Name::new("destination"), destination);
+        fields.insert(
+            async_graphql::Name::new("deadline"),
+            async_graphql::InputType::to_value(&self.deadline),
+        );
+        fields.insert(
+            async_graphql::Name::new("nonce"),
+            async_graphql::InputType::to_value(&self.nonce),
+        );
+
+        if let Some(merkle_proof) = &self.merkle_proof {
+            fields.insert(
+                async_graphql::Name::new("merkleProof"),
+                async_graphql::ScalarType::to_value(merkle_proof),
+            );
+        }
+
+        if let Some(relayer_fee) = &self.relayer_fee {
+            fields.insert(
+                async_graphql::Name::new("relayerFee"),
+                async_graphql::InputType::to_value(relayer_fee),
+            );
+        }
 
         async_graphql::Value::Object(fields)
     }
@@ -193,5 +490,8 @@ impl async_graphql::ScalarType for AirDropClaim {
 
 /// The [EIP-155] constant for the Ethereum mainnet.
 ///
+/// Snapshots taken on other chains use the chain ID of their [`EvmChain`].
+///
 /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
-pub const ETHEREUM_MAINNET_CHAIN_ID: alloy_primitives::aliases::ChainId = 1;
+pub const ETHEREUM_MAINNET_CHAIN_ID: alloy_primitives::aliases::ChainId =
+    EvmChain::Ethereum.chain_id();
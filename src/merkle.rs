@@ -0,0 +1,135 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Verification of Merkle proofs for snapshot allowlists.
+//!
+//! The snapshot is a Merkle tree where each leaf is the Keccak-256 hash of a claimer's Ethereum
+//! address (20 bytes) followed by its snapshot balance (32 bytes, big-endian). Inner nodes are the
+//! Keccak-256 hash of the concatenation of their two children, sorted so that the proof doesn't
+//! need to specify on which side each sibling is. Since leaves are hashed from 52 bytes and inner
+//! nodes from 64 bytes, a leaf can't be confused with an inner node.
+
+use std::str::FromStr;
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// A proof that an address had a certain balance in a snapshot committed to by a Merkle root.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MerkleProof {
+    pub balance: U256,
+    pub siblings: Vec<B256>,
+}
+
+impl MerkleProof {
+    /// Checks if this [`MerkleProof`] shows that `address` is part of the snapshot with the
+    /// specified Merkle `root`.
+    pub fn verify(&self, root: B256, address: &Address) -> bool {
+        let computed_root = self
+            .siblings
+            .iter()
+            .fold(merkle_leaf(address, self.balance), |node, sibling| {
+                merkle_node(node, *sibling)
+            });
+
+        computed_root == root
+    }
+}
+
+/// Computes the hash of a Merkle tree leaf for an `address` with a snapshot `balance`.
+pub fn merkle_leaf(address: &Address, balance: U256) -> B256 {
+    let mut leaf_data = [0_u8; 52];
+
+    leaf_data[..20].copy_from_slice(address.as_slice());
+    leaf_data[20..].copy_from_slice(&balance.to_be_bytes::<32>());
+
+    keccak256(leaf_data)
+}
+
+/// Computes the hash of an inner Merkle tree node from its two children.
+pub fn merkle_node(left: B256, right: B256) -> B256 {
+    let (first, second) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+
+    let mut node_data = [0_u8; 64];
+
+    node_data[..32].copy_from_slice(first.as_slice());
+    node_data[32..].copy_from_slice(second.as_slice());
+
+    keccak256(node_data)
+}
+
+#[async_graphql::Scalar]
+impl async_graphql::ScalarType for MerkleProof {
+    fn parse(value: async_graphql::Value) -> async_graphql::InputValueResult<Self> {
+        let async_graphql::Value::Object(mut fields) = value else {
+            return Err(async_graphql::InputValueError::expected_type(value));
+        };
+
+        if fields.len() != 2 {
+            return Err(async_graphql::InputValueError::custom(
+                "`MerkleProof` object must have exactly two fields: `balance` and `siblings`",
+            ));
+        }
+
+        let Some(async_graphql::Value::String(balance_string)) = fields.swap_remove("balance")
+        else {
+            return Err(async_graphql::InputValueError::custom(
+                "`MerkleProof` object is missing a `balance` string field",
+            ));
+        };
+
+        let balance = U256::from_str(&balance_string).map_err(|_| {
+            async_graphql::InputValueError::custom(
+                "`MerkleProof`'s `balance` is not a valid balance value",
+            )
+        })?;
+
+        let Some(async_graphql::Value::List(sibling_values)) = fields.swap_remove("siblings")
+        else {
+            return Err(async_graphql::InputValueError::custom(
+                "`MerkleProof` object is missing a `siblings` list field",
+            ));
+        };
+
+        let siblings = sibling_values
+            .into_iter()
+            .map(|sibling_value| {
+                let async_graphql::Value::String(sibling_string) = sibling_value else {
+                    return Err(async_graphql::InputValueError::custom(
+                        "`MerkleProof`'s `siblings` must be a list of strings",
+                    ));
+                };
+
+                B256::from_str(&sibling_string).map_err(|_| {
+                    async_graphql::InputValueError::custom(
+                        "`MerkleProof`'s `siblings` must be 32-byte hexadecimal strings",
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(MerkleProof { balance, siblings })
+    }
+
+    fn to_value(&self) -> async_graphql::Value {
+        let mut fields = IndexMap::new();
+
+        let balance = async_graphql::Value::String(self.balance.to_string());
+        let siblings = async_graphql::Value::List(
+            self.siblings
+                .iter()
+                .map(|sibling| async_graphql::Value::String(sibling.to_string()))
+                .collect(),
+        );
+
+        fields.insert(async_graphql::Name::new("balance"), balance);
+        fields.insert(async_graphql::Name::new("siblings"), siblings);
+
+        async_graphql::Value::Object(fields)
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Normalization of the query results returned by Space-and-Time.
+//!
+//! The service only forwards the normalized values to the contract, so every validator agrees on
+//! the result regardless of how the Gateway encoded it. Column names are matched ignoring their
+//! casing, and numeric values are accepted as JSON numbers or as decimal or hexadecimal strings.
+//! Anything that can't be interpreted unambiguously is rejected.
+
+use std::str::FromStr;
+
+use alloy_primitives::{Address, U256};
+use linera_sdk::serde_json;
+
+use crate::AirDropError;
+
+/// The name of the optional column with the block of a row.
+const BLOCK_NUMBER_COLUMN: &str = "BLOCK_NUMBER";
+
+/// The name of the column with the wallet of a row, in queries with several wallets.
+const WALLET_ADDRESS_COLUMN: &str = "WALLET_ADDRESS";
+
+/// The largest integer that is exactly representable as a JSON floating point number.
+const MAX_EXACT_FLOAT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// A balance read from the result of a Space-and-Time query.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SnapshotBalance {
+    /// The value of the queried column.
+    pub balance: U256,
+    /// The block of the row, if the query selected its `BLOCK_NUMBER`.
+    pub block_number: Option<u64>,
+}
+
+/// A balance of a wallet read from the result of a Space-and-Time query with several wallets.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WalletBalance {
+    /// The wallet in the row's `WALLET_ADDRESS`.
+    pub wallet_address: Address,
+    /// The balance in the row.
+    pub snapshot: SnapshotBalance,
+}
+
+/// Normalizes the `response` body of a Space-and-Time query that returns at most one row, with
+/// the numeric `column` and optionally the row's `BLOCK_NUMBER`.
+///
+/// Returns [`None`] if the query returned no rows.
+pub fn normalize_snapshot_balance(
+    response: &[u8],
+    column: &str,
+) -> Result<Option<SnapshotBalance>, AirDropError> {
+    let rows = parse_rows(response)?;
+
+    match rows.as_slice() {
+        [] => Ok(None),
+        [row] => Ok(Some(normalize_row(row, column, false)?.1)),
+        _ => Err(AirDropError::MalformedOracleResponse(format!(
+            "Expected at most one query result from Space-and-Time, got {}",
+            rows.len()
+        ))),
+    }
+}
+
+/// Normalizes the `response` body of a Space-and-Time query that returns rows of several
+/// wallets, each one with its `WALLET_ADDRESS`, the numeric `column` and optionally the row's
+/// `BLOCK_NUMBER`.
+///
+/// The rows are returned in the same order as in the response.
+pub fn normalize_wallet_balances(
+    response: &[u8],
+    column: &str,
+) -> Result<Vec<WalletBalance>, AirDropError> {
+    parse_rows(response)?
+        .iter()
+        .map(|row| {
+            let (wallet_address, snapshot) = normalize_row(row, column, true)?;
+
+            let wallet_address = wallet_address.ok_or_else(|| {
+                AirDropError::MalformedOracleResponse(format!(
+                    "Missing {WALLET_ADDRESS_COLUMN:?} column in Space-and-Time query result"
+                ))
+            })?;
+
+            Ok(WalletBalance {
+                wallet_address,
+                snapshot,
+            })
+        })
+        .collect()
+}
+
+/// Parses the rows in the `response` body of a Space-and-Time query.
+fn parse_rows(
+    response: &[u8],
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, AirDropError> {
+    serde_json::from_slice(response).map_err(|_| {
+        AirDropError::MalformedOracleResponse(
+            "Invalid response from Space-and-Time Gateway".to_owned(),
+        )
+    })
+}
+
+/// Normalizes a `row` with the numeric `column` and optionally its `BLOCK_NUMBER`.
+///
+/// If `has_wallets` is `true`, the row may also have a `WALLET_ADDRESS`, which is returned with
+/// the balance.
+fn normalize_row(
+    row: &serde_json::Map<String, serde_json::Value>,
+    column: &str,
+    has_wallets: bool,
+) -> Result<(Option<Address>, SnapshotBalance), AirDropError> {
+    let mut balance = None;
+    let mut block_number = None;
+    let mut wallet = None;
+
+    for (name, value) in row {
+        let slot = if name.eq_ignore_ascii_case(column) {
+            &mut balance
+        } else if name.eq_ignore_ascii_case(BLOCK_NUMBER_COLUMN) {
+            &mut block_number
+        } else if has_wallets && name.eq_ignore_ascii_case(WALLET_ADDRESS_COLUMN) {
+            &mut wallet
+        } else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Unexpected column in Space-and-Time query result: {name:?}"
+            )));
+        };
+
+        if slot.replace(value).is_some() {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Duplicate column in Space-and-Time query result: {name:?}"
+            )));
+        }
+    }
+
+    let balance = balance.ok_or_else(|| {
+        AirDropError::MalformedOracleResponse(format!(
+            "Missing {column:?} column in Space-and-Time query result"
+        ))
+    })?;
+
+    let block_number = block_number
+        .map(|value| {
+            let block_number = normalize_numeric(value)?;
+
+            u64::try_from(block_number).map_err(|_| {
+                AirDropError::MalformedOracleResponse(format!(
+                    "Block number is too large: {block_number}"
+                ))
+            })
+        })
+        .transpose()?;
+
+    let wallet_address = wallet
+        .map(|wallet| {
+            wallet
+                .as_str()
+                .and_then(|address| Address::from_str(address.trim()).ok())
+                .ok_or_else(|| {
+                    AirDropError::MalformedOracleResponse(format!(
+                        "Query result is not a valid wallet address: {wallet}"
+                    ))
+                })
+        })
+        .transpose()?;
+
+    let snapshot = SnapshotBalance {
+        balance: normalize_numeric(balance)?,
+        block_number,
+    };
+
+    Ok((wallet_address, snapshot))
+}
+
+/// Normalizes a non-negative integer `value`, encoded as a JSON number or as a decimal or
+/// `0x`-prefixed hexadecimal string.
+///
+/// Decimal strings may have a fractional part, as long as it only has zeros. JSON numbers that
+/// aren't exactly representable are rejected.
+pub fn normalize_numeric(value: &serde_json::Value) -> Result<U256, AirDropError> {
+    let malformed = || {
+        AirDropError::MalformedOracleResponse(format!(
+            "Query result is not a valid numeric value: {value}"
+        ))
+    };
+
+    match value {
+        serde_json::Value::Number(number) => {
+            if let Some(integer) = number.as_u64() {
+                return Ok(U256::from(integer));
+            }
+
+            match number.as_f64() {
+                Some(float)
+                    if float >= 0.0 && float.fract() == 0.0 && float <= MAX_EXACT_FLOAT_INTEGER =>
+                {
+                    Ok(U256::from(float as u64))
+                }
+                _ => Err(malformed()),
+            }
+        }
+        serde_json::Value::String(string) => {
+            let trimmed = string.trim();
+
+            let (digits, radix) = match trimmed
+                .strip_prefix("0x")
+                .or_else(|| trimmed.strip_prefix("0X"))
+            {
+                Some(hex_digits) => (hex_digits, 16),
+                None => match trimmed.split_once('.') {
+                    Some((integer, fraction)) if fraction.bytes().all(|digit| digit == b'0') => {
+                        (integer, 10)
+                    }
+                    Some(_) => return Err(malformed()),
+                    None => (trimmed, 10),
+                },
+            };
+
+            if digits.is_empty() || !digits.chars().all(|digit| digit.is_digit(radix)) {
+                return Err(malformed());
+            }
+
+            U256::from_str_radix(digits, radix as u64).map_err(|_| malformed())
+        }
+        _ => Err(malformed()),
+    }
+}
@@ -0,0 +1,475 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The sources of snapshot balances used to decide if an address is eligible for the airdrop.
+//!
+//! Oracles that verify evidence included in the claim (an allowlist or a [`MerkleProof`]) are
+//! deterministic and run inside the contract. Oracles that perform HTTP requests run inside the
+//! service, which filters out the non-deterministic parts of the responses before they reach the
+//! contract.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use alloy_primitives::{Address, B256, U256};
+use linera_sdk::{ensure, http, serde_json};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    normalize_snapshot_balance, normalize_wallet_balances, AirDropError, Comparison, EvmChain,
+    MerkleProof, RuleOracle, SelectQuery, SnapshotAsset, SqlValue,
+};
+
+/// The URL of the Space-and-Time Gateway API.
+pub const SXT_GATEWAY_URL: &str = "https://api.spaceandtime.dev/v1/sql";
+
+/// The maximum number of addresses whose balances are queried from Space-and-Time at once.
+pub const MAX_ADDRESSES_PER_QUERY: usize = 100;
+
+/// The Space-and-Time table with the transactions, in each chain's schema.
+const TRANSACTIONS_TABLE: &str = "TRANSACTIONS";
+
+/// The source of snapshot balances used by an airdrop, selected in its
+/// [`Parameters`](crate::Parameters).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EligibilitySource {
+    /// Queries Space-and-Time through its Gateway, using the API token in the claim.
+    ///
+    /// The tables are read from the schema of the [`Parameters`](crate::Parameters)'s source
+    /// chain.
+    SpaceAndTime,
+    /// Queries the balance at the snapshot block from a JSON-RPC endpoint of the source chain.
+    JsonRpc { url: String },
+    /// A static list of the eligible addresses and their snapshot balances.
+    Allowlist(BTreeMap<Address, U256>),
+    /// The root of a Merkle tree of `(address, balance)` leaves with a precomputed snapshot.
+    ///
+    /// Claims must include a [`MerkleProof`] of the claimer's balance.
+    MerkleRoot(B256),
+}
+
+/// An oracle that provides the balances of addresses at the airdrop's snapshot block.
+pub trait EligibilityOracle {
+    /// Returns the balance that `address` had at the snapshot block.
+    ///
+    /// Addresses that aren't part of the snapshot are rejected with
+    /// [`AirDropError::NotEligible`].
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError>;
+}
+
+/// An [`EligibilityOracle`] that looks up balances in a static allowlist.
+pub struct AllowlistOracle<'list> {
+    balances: &'list BTreeMap<Address, U256>,
+}
+
+impl<'list> AllowlistOracle<'list> {
+    /// Creates an [`AllowlistOracle`] for the allowlisted `balances`.
+    pub fn new(balances: &'list BTreeMap<Address, U256>) -> Self {
+        AllowlistOracle { balances }
+    }
+}
+
+impl EligibilityOracle for AllowlistOracle<'_> {
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError> {
+        self.balances
+            .get(address)
+            .copied()
+            .ok_or(AirDropError::NotEligible)
+    }
+}
+
+/// An [`EligibilityOracle`] that verifies a claimer's [`MerkleProof`] against a Merkle root.
+pub struct MerkleOracle<'proof> {
+    root: B256,
+    proof: Option<&'proof MerkleProof>,
+}
+
+impl<'proof> MerkleOracle<'proof> {
+    /// Creates a [`MerkleOracle`] to verify the `proof` in a claim against the Merkle `root`.
+    pub fn new(root: B256, proof: Option<&'proof MerkleProof>) -> Self {
+        MerkleOracle { root, proof }
+    }
+}
+
+impl EligibilityOracle for MerkleOracle<'_> {
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError> {
+        let proof = self.proof.ok_or(AirDropError::MissingMerkleProof)?;
+
+        ensure!(
+            proof.verify(self.root, address),
+            AirDropError::InvalidMerkleProof
+        );
+
+        Ok(proof.balance)
+    }
+}
+
+/// An [`EligibilityOracle`] that queries Space-and-Time through its Gateway.
+pub struct SpaceAndTimeOracle<'input, Http> {
+    chain: EvmChain,
+    asset: &'input SnapshotAsset,
+    snapshot_block: u64,
+    api_token: &'input str,
+    http: Http,
+}
+
+impl<'input, Http> SpaceAndTimeOracle<'input, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    /// Creates a [`SpaceAndTimeOracle`] for the balances of the `asset` on the `chain` at the
+    /// `snapshot_block`, which authenticates with the `api_token` and performs requests using
+    /// `http`.
+    pub fn new(
+        chain: EvmChain,
+        asset: &'input SnapshotAsset,
+        snapshot_block: u64,
+        api_token: &'input str,
+        http: Http,
+    ) -> Self {
+        SpaceAndTimeOracle {
+            chain,
+            asset,
+            snapshot_block,
+            api_token,
+            http,
+        }
+    }
+}
+
+impl<Http> SpaceAndTimeOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    /// Returns the balances of `asset` that each of the `addresses` held at the `block`.
+    ///
+    /// The addresses are queried in chunks of at most [`MAX_ADDRESSES_PER_QUERY`], and only the
+    /// latest row of each address at or before the `block` is selected. Addresses without a
+    /// balance at the `block` are missing from the result.
+    pub fn balances_at(
+        &mut self,
+        addresses: &[Address],
+        asset: &SnapshotAsset,
+        block: u64,
+    ) -> Result<BTreeMap<Address, U256>, AirDropError> {
+        let mut balances = BTreeMap::new();
+
+        for chunk in addresses.chunks(MAX_ADDRESSES_PER_QUERY) {
+            let mut query = SelectQuery::new(
+                "WALLET_ADDRESS, BALANCE",
+                self.chain.sxt_schema(),
+                asset.balances_table(),
+            )
+            .filter_in(
+                "WALLET_ADDRESS",
+                chunk.iter().copied().map(SqlValue::Address),
+            );
+
+            for (column, value) in asset.balances_filters() {
+                query = query.filter(column, Comparison::Equal, value);
+            }
+
+            let query = query
+                .filter(
+                    "BLOCK_NUMBER",
+                    Comparison::LessOrEqual,
+                    SqlValue::Integer(block),
+                )
+                .latest_per_group("WALLET_ADDRESS", "BLOCK_NUMBER")
+                .build();
+
+            let response = self.send_query(&query)?;
+
+            for row in normalize_wallet_balances(&response, "BALANCE")? {
+                ensure!(
+                    chunk.contains(&row.wallet_address),
+                    AirDropError::MalformedOracleResponse(format!(
+                        "Space-and-Time returned a balance of an unexpected wallet: {}",
+                        row.wallet_address
+                    ))
+                );
+
+                let previous_balance = balances.insert(row.wallet_address, row.snapshot.balance);
+
+                ensure!(
+                    previous_balance.is_none(),
+                    AirDropError::MalformedOracleResponse(format!(
+                        "Space-and-Time returned more than one balance of wallet {}",
+                        row.wallet_address
+                    ))
+                );
+            }
+        }
+
+        Ok(balances)
+    }
+}
+
+impl<Http> SpaceAndTimeOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    /// Runs an SQL `query` that returns at most one row, with a numeric `column`.
+    ///
+    /// Returns [`None`] if the query returned no rows.
+    fn query_value(&mut self, query: &str, column: &str) -> Result<Option<U256>, AirDropError> {
+        let response = self.send_query(query)?;
+
+        Ok(normalize_snapshot_balance(&response, column)?.map(|row| row.balance))
+    }
+
+    /// Sends an SQL `query` to the Space-and-Time Gateway, and returns the response's body.
+    fn send_query(&mut self, query: &str) -> Result<Vec<u8>, AirDropError> {
+        let body = serde_json::json!({ "sqlText": query }).to_string();
+
+        let response = (self.http)(
+            http::Request::post(SXT_GATEWAY_URL, body.as_bytes())
+                .with_header("Content-Type", b"application/json")
+                .with_header(
+                    "Authorization",
+                    format!("Bearer {}", self.api_token).as_bytes(),
+                ),
+        );
+
+        ensure!(
+            response.status == 200,
+            AirDropError::OracleFailure(format!(
+                "Failed to perform Space-and-Time query. Status-code: {}",
+                response.status
+            ))
+        );
+
+        Ok(response.body)
+    }
+}
+
+impl<Http> EligibilityOracle for SpaceAndTimeOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError> {
+        self.balance_at(address, self.asset, self.snapshot_block)
+    }
+}
+
+impl<Http> RuleOracle for SpaceAndTimeOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    fn balance_at(
+        &mut self,
+        address: &Address,
+        asset: &SnapshotAsset,
+        block: u64,
+    ) -> Result<U256, AirDropError> {
+        let mut query =
+            SelectQuery::new("BALANCE", self.chain.sxt_schema(), asset.balances_table()).filter(
+                "WALLET_ADDRESS",
+                Comparison::Equal,
+                SqlValue::Address(*address),
+            );
+
+        for (column, value) in asset.balances_filters() {
+            query = query.filter(column, Comparison::Equal, value);
+        }
+
+        let query = query
+            .filter(
+                "BLOCK_NUMBER",
+                Comparison::LessOrEqual,
+                SqlValue::Integer(block),
+            )
+            .order_by_descending("BLOCK_NUMBER")
+            .limit(1)
+            .build();
+
+        self.query_value(&query, "BALANCE")?
+            .ok_or(AirDropError::NotEligible)
+    }
+
+    fn transaction_count(
+        &mut self,
+        address: &Address,
+        before_block: u64,
+    ) -> Result<u64, AirDropError> {
+        let query = SelectQuery::new(
+            "CAST(COUNT(*) AS VARCHAR) AS TRANSACTION_COUNT",
+            self.chain.sxt_schema(),
+            TRANSACTIONS_TABLE,
+        )
+        .filter(
+            "FROM_ADDRESS",
+            Comparison::Equal,
+            SqlValue::Address(*address),
+        )
+        .filter(
+            "BLOCK_NUMBER",
+            Comparison::Less,
+            SqlValue::Integer(before_block),
+        )
+        .build();
+
+        let count = self
+            .query_value(&query, "TRANSACTION_COUNT")?
+            .unwrap_or(U256::ZERO);
+
+        u64::try_from(count).map_err(|_| {
+            AirDropError::MalformedOracleResponse(format!(
+                "Transaction count is too large: {count}"
+            ))
+        })
+    }
+}
+
+/// An [`EligibilityOracle`] that queries the balance of addresses at the snapshot block from an
+/// Ethereum JSON-RPC endpoint.
+///
+/// Native balances are queried using `eth_getBalance`, and token balances by calling the token
+/// contract's `balanceOf` function using `eth_call`.
+pub struct JsonRpcOracle<'input, Http> {
+    url: &'input str,
+    asset: &'input SnapshotAsset,
+    snapshot_block: u64,
+    http: Http,
+}
+
+impl<'input, Http> JsonRpcOracle<'input, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    /// Creates a [`JsonRpcOracle`] for the balances of the `asset` at the `snapshot_block`, which
+    /// sends requests to the endpoint at `url` using `http`.
+    pub fn new(
+        url: &'input str,
+        asset: &'input SnapshotAsset,
+        snapshot_block: u64,
+        http: Http,
+    ) -> Self {
+        JsonRpcOracle {
+            url,
+            asset,
+            snapshot_block,
+            http,
+        }
+    }
+}
+
+impl<Http> JsonRpcOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    /// Sends a JSON-RPC request to call the `method` with the `params`, which returns a
+    /// hexadecimal quantity.
+    fn call(&mut self, method: &str, params: serde_json::Value) -> Result<U256, AirDropError> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = (self.http)(
+            http::Request::post(self.url, request.to_string().as_bytes())
+                .with_header("Content-Type", b"application/json"),
+        );
+
+        ensure!(
+            response.status == 200,
+            AirDropError::OracleFailure(format!(
+                "Failed to perform JSON-RPC request. Status-code: {}",
+                response.status
+            ))
+        );
+
+        let mut result =
+            serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&response.body)
+                .map_err(|_| {
+                    AirDropError::MalformedOracleResponse(
+                        "Invalid response from JSON-RPC endpoint".to_owned(),
+                    )
+                })?;
+
+        if let Some(error) = result.remove("error") {
+            return Err(AirDropError::OracleFailure(format!(
+                "JSON-RPC request failed: {error}"
+            )));
+        }
+
+        let Some(serde_json::Value::String(value_string)) = result.remove("result") else {
+            return Err(AirDropError::MalformedOracleResponse(format!(
+                "Missing result in JSON-RPC response: {result:?}"
+            )));
+        };
+
+        U256::from_str(&value_string).map_err(|_| {
+            AirDropError::MalformedOracleResponse(format!(
+                "JSON-RPC result is not a valid quantity: {value_string:?}"
+            ))
+        })
+    }
+}
+
+impl<Http> EligibilityOracle for JsonRpcOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    fn snapshot_balance(&mut self, address: &Address) -> Result<U256, AirDropError> {
+        self.balance_at(address, self.asset, self.snapshot_block)
+    }
+}
+
+impl<Http> RuleOracle for JsonRpcOracle<'_, Http>
+where
+    Http: FnMut(http::Request) -> http::Response,
+{
+    fn balance_at(
+        &mut self,
+        address: &Address,
+        asset: &SnapshotAsset,
+        block: u64,
+    ) -> Result<U256, AirDropError> {
+        let block = format!("0x{block:x}");
+
+        match asset.balance_call(*address) {
+            None => self.call(
+                "eth_getBalance",
+                serde_json::json!([format!("0x{}", hex::encode(address.as_slice())), block]),
+            ),
+            Some((contract, call_data)) => self.call(
+                "eth_call",
+                serde_json::json!([
+                    {
+                        "to": format!("0x{}", hex::encode(contract.as_slice())),
+                        "data": format!("0x{}", hex::encode(call_data)),
+                    },
+                    block,
+                ]),
+            ),
+        }
+    }
+
+    /// Returns the number of transactions sent by `address` before the block `before_block`,
+    /// which is the account's nonce at the end of the previous block.
+    fn transaction_count(
+        &mut self,
+        address: &Address,
+        before_block: u64,
+    ) -> Result<u64, AirDropError> {
+        if before_block == 0 {
+            return Ok(0);
+        }
+
+        let count = self.call(
+            "eth_getTransactionCount",
+            serde_json::json!([
+                format!("0x{}", hex::encode(address.as_slice())),
+                format!("0x{:x}", before_block - 1),
+            ]),
+        )?;
+
+        u64::try_from(count).map_err(|_| {
+            AirDropError::MalformedOracleResponse(format!(
+                "Transaction count is too large: {count}"
+            ))
+        })
+    }
+}
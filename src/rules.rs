@@ -0,0 +1,142 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Composite rules that claimers must satisfy to be eligible for the airdrop.
+//!
+//! A rule is a tree where each leaf is a criterion checked with a single query, and inner nodes
+//! combine their children with `AND` ([`EligibilityRule::All`]) or `OR`
+//! ([`EligibilityRule::Any`]). Rules are evaluated by the service, which explains which criteria
+//! were (or weren't) met.
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{AirDropError, SnapshotAsset};
+
+/// A rule that decides if a claimer is eligible for the airdrop.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EligibilityRule {
+    /// The claimer held at least the `minimum` balance of the `asset` at the snapshot block.
+    Holds { asset: SnapshotAsset, minimum: U256 },
+    /// The claimer sent at least `minimum` transactions before the block `before_block`.
+    Transactions { minimum: u64, before_block: u64 },
+    /// All of the rules are satisfied.
+    All(Vec<EligibilityRule>),
+    /// At least one of the rules is satisfied.
+    Any(Vec<EligibilityRule>),
+}
+
+/// The result of evaluating an [`EligibilityRule`] for a claimer.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, async_graphql::SimpleObject)]
+pub struct RuleEvaluation {
+    /// If the claimer satisfies the rule.
+    pub satisfied: bool,
+    /// A description of the criteria that were checked and their results.
+    pub explanation: String,
+}
+
+/// An oracle that answers the queries of the leaves of an [`EligibilityRule`].
+pub trait RuleOracle {
+    /// Returns the balance of `asset` that `address` held at the `block`.
+    ///
+    /// Addresses without a balance at the `block` are rejected with
+    /// [`AirDropError::NotEligible`].
+    fn balance_at(
+        &mut self,
+        address: &Address,
+        asset: &SnapshotAsset,
+        block: u64,
+    ) -> Result<U256, AirDropError>;
+
+    /// Returns the number of transactions sent by `address` before the block `before_block`.
+    fn transaction_count(
+        &mut self,
+        address: &Address,
+        before_block: u64,
+    ) -> Result<u64, AirDropError>;
+}
+
+impl EligibilityRule {
+    /// Evaluates this rule for the `address`, using the `oracle` to query the criteria of the
+    /// leaves, and the balances at the `snapshot_block`.
+    ///
+    /// The children of [`EligibilityRule::All`] and [`EligibilityRule::Any`] are evaluated in
+    /// order, stopping as soon as the result is known.
+    pub fn evaluate(
+        &self,
+        address: &Address,
+        snapshot_block: u64,
+        oracle: &mut impl RuleOracle,
+    ) -> Result<RuleEvaluation, AirDropError> {
+        match self {
+            EligibilityRule::Holds { asset, minimum } => {
+                let balance = match oracle.balance_at(address, asset, snapshot_block) {
+                    Err(AirDropError::NotEligible) => U256::ZERO,
+                    result => result?,
+                };
+
+                Ok(RuleEvaluation {
+                    satisfied: balance >= *minimum,
+                    explanation: format!(
+                        "held {balance} of {asset:?} at block {snapshot_block}, \
+                        needed at least {minimum}"
+                    ),
+                })
+            }
+            EligibilityRule::Transactions {
+                minimum,
+                before_block,
+            } => {
+                let count = oracle.transaction_count(address, *before_block)?;
+
+                Ok(RuleEvaluation {
+                    satisfied: count >= *minimum,
+                    explanation: format!(
+                        "sent {count} transactions before block {before_block}, \
+                        needed at least {minimum}"
+                    ),
+                })
+            }
+            EligibilityRule::All(rules) => {
+                Self::combine(rules, "all of", false, address, snapshot_block, oracle)
+            }
+            EligibilityRule::Any(rules) => {
+                Self::combine(rules, "any of", true, address, snapshot_block, oracle)
+            }
+        }
+    }
+
+    /// Evaluates the `rules` in order, until one of them has the `decisive` result.
+    ///
+    /// The combination is satisfied if a decisive result is found and `decisive` is `true`, or if
+    /// no decisive result is found and `decisive` is `false`.
+    fn combine(
+        rules: &[EligibilityRule],
+        label: &str,
+        decisive: bool,
+        address: &Address,
+        snapshot_block: u64,
+        oracle: &mut impl RuleOracle,
+    ) -> Result<RuleEvaluation, AirDropError> {
+        let mut explanations = Vec::with_capacity(rules.len());
+
+        for rule in rules {
+            let evaluation = rule.evaluate(address, snapshot_block, oracle)?;
+            let mark = if evaluation.satisfied { "yes" } else { "no" };
+
+            explanations.push(format!("{mark}: {}", evaluation.explanation));
+
+            if evaluation.satisfied == decisive {
+                return Ok(RuleEvaluation {
+                    satisfied: decisive,
+                    explanation: format!("{label} [{}]", explanations.join("; ")),
+                });
+            }
+        }
+
+        Ok(RuleEvaluation {
+            satisfied: !decisive,
+            explanation: format!("{label} [{}]", explanations.join("; ")),
+        })
+    }
+}
@@ -7,18 +7,29 @@
 mod service_unit_tests;
 mod state;
 
-use std::{str::FromStr, sync::Arc};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 
-use airdrop_demo::{AirDropClaim, Parameters};
-use alloy_primitives::U256;
+use airdrop_demo::{
+    AirDropClaim, AirDropError, AirDropId, ClaimRevocation, EligibilityOracle, EligibilityRule,
+    EligibilitySource, JsonRpcOracle, MerkleProof, Operation, Parameters, RelayerFee,
+    RuleEvaluation, RuleOracle, SnapshotAsset, SnapshotSampling, SpaceAndTimeOracle,
+    MAX_CLAIM_BATCH_SIZE,
+};
+use alloy_primitives::{Address, U256};
 use async_graphql::{EmptySubscription, Schema};
 use linera_sdk::{
-    abis::fungible, bcs, ensure, http, linera_base_types::WithServiceAbi, serde_json, Service,
-    ServiceRuntime,
+    abis::fungible,
+    bcs,
+    linera_base_types::{Amount, ChainId, Timestamp, WithServiceAbi},
+    views::View,
+    Service, ServiceRuntime,
 };
 
+use self::state::{Application, ClaimRecord};
+
 #[derive(Clone)]
 pub struct ApplicationService {
+    state: Arc<Application>,
     runtime: Arc<ServiceRuntime<Self>>,
 }
 
@@ -32,7 +43,11 @@ impl Service for ApplicationService {
     type Parameters = Parameters;
 
     async fn new(runtime: ServiceRuntime<Self>) -> Self {
+        let state = Application::load(runtime.root_view_storage_context())
+            .await
+            .expect("Failed to load state");
         ApplicationService {
+            state: Arc::new(state),
             runtime: Arc::new(runtime),
         }
     }
@@ -50,81 +65,413 @@ pub struct Query(ApplicationService);
 
 #[async_graphql::Object]
 impl Query {
+    /// Returns the [`Amount`] of tokens that can still be distributed by this chain.
+    async fn remaining_budget(&self) -> Amount {
+        let chain_id = self.0.runtime.chain_id();
+        let budget = self
+            .0
+            .runtime
+            .application_parameters()
+            .payer_budget(chain_id);
+
+        budget.saturating_sub(*self.0.state.distributed_total.get())
+    }
+
+    /// Returns whether the airdrop is paused.
+    async fn paused(&self) -> bool {
+        *self.0.state.paused.get()
+    }
+
+    /// Returns the aggregate [`Statistics`] of the airdrop campaign.
+    async fn stats(&self) -> Statistics {
+        let state = &self.0.state;
+
+        Statistics {
+            claims_paid: *state.claims_paid.get(),
+            total_distributed: *state.distributed_total.get(),
+            destination_chains: *state.destination_chain_count.get(),
+            rejected_replays: *state.rejected_replays.get(),
+            rejected_revoked_claims: *state.rejected_revoked_claims.get(),
+        }
+    }
+
+    /// Returns the [`ClaimRecord`] of the airdrop paid for an `address`, if it has been paid.
+    async fn claim_record(&self, address: AirDropId) -> async_graphql::Result<Option<ClaimRecord>> {
+        Ok(self.0.state.handled_airdrops.get(&address).await?)
+    }
+
+    /// Returns a page of the history of paid airdrops.
+    ///
+    /// Skips the first `offset` records and returns at most `limit` records, up to
+    /// [`MAX_CLAIM_RECORDS_PAGE_SIZE`].
+    async fn claim_records(
+        &self,
+        #[graphql(default)] offset: usize,
+        #[graphql(default_with = "MAX_CLAIM_RECORDS_PAGE_SIZE")] limit: usize,
+    ) -> async_graphql::Result<Vec<ClaimRecordEntry>> {
+        let limit = limit.min(MAX_CLAIM_RECORDS_PAGE_SIZE);
+        let mut entries = Vec::with_capacity(limit);
+        let mut skipped = 0;
+
+        if limit == 0 {
+            return Ok(entries);
+        }
+
+        self.0
+            .state
+            .handled_airdrops
+            .for_each_index_value_while(|address, record| {
+                if skipped < offset {
+                    skipped += 1;
+                } else {
+                    entries.push(ClaimRecordEntry {
+                        address: address.external_address().to_checksum(None),
+                        record: record.into_owned(),
+                    });
+                }
+
+                Ok(entries.len() < limit)
+            })
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Returns the [EIP-155] chain ID of the EVM chain where the snapshot is taken, which is part
+    /// of the EIP-712 domain of the claims' signatures.
+    ///
+    /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+    async fn source_chain_id(&self) -> u64 {
+        self.0
+            .runtime
+            .application_parameters()
+            .source_chain
+            .chain_id()
+    }
+
+    /// Returns the earliest [`Timestamp`] at which claims are accepted.
+    async fn claim_start(&self) -> Timestamp {
+        self.0.runtime.application_parameters().claim_start
+    }
+
+    /// Returns the [`Timestamp`] from which claims are no longer accepted.
+    async fn claim_end(&self) -> Timestamp {
+        self.0.runtime.application_parameters().claim_end
+    }
+
     /// Checks if an address is eligible to claim an airdrop.
+    ///
+    /// Returns the address's balance at the snapshot block if it is eligible, or `null` otherwise.
+    /// If the airdrop samples balances at several blocks, the returned balance combines the
+    /// samples.
     async fn check_eligibility(
         &self,
         address: String,
         api_token: String,
-    ) -> async_graphql::Result<bool> {
-        let lowercase_address = address.to_lowercase();
+    ) -> async_graphql::Result<Option<String>> {
+        let address = parse_address(&address)?;
+
+        let parameters = self.0.runtime.application_parameters();
+
+        check_eligibility_is_queried(&parameters)?;
+
+        match self
+            .0
+            .query_snapshot_balance(&address, &api_token, &parameters)
+        {
+            Ok(balance) => Ok((balance >= parameters.minimum_balance).then(|| balance.to_string())),
+            Err(AirDropError::NotEligible) => Ok(None),
+            Err(error) => Err(async_graphql::Error::new(error.to_string())),
+        }
+    }
+
+    /// Checks if each of the `addresses` is eligible to claim an airdrop.
+    ///
+    /// Space-and-Time is queried for many addresses at once, in chunks of at most
+    /// [`MAX_ADDRESSES_PER_QUERY`] addresses. At most [`MAX_ELIGIBILITY_BATCH_SIZE`] addresses can
+    /// be checked in a single query.
+    async fn check_eligibility_batch(
+        &self,
+        addresses: Vec<String>,
+        api_token: String,
+    ) -> async_graphql::Result<Vec<EligibilityEntry>> {
+        if addresses.len() > MAX_ELIGIBILITY_BATCH_SIZE {
+            return Err(async_graphql::Error::new(format!(
+                "At most {MAX_ELIGIBILITY_BATCH_SIZE} addresses can be checked at once"
+            )));
+        }
+
+        let addresses = addresses
+            .iter()
+            .map(|address| parse_address(address))
+            .collect::<async_graphql::Result<Vec<_>>>()?;
+        let parameters = self.0.runtime.application_parameters();
+
+        check_eligibility_is_queried(&parameters)?;
+
+        let balances = self
+            .0
+            .query_snapshot_balances(&addresses, &api_token, &parameters)
+            .map_err(|error| async_graphql::Error::new(error.to_string()))?;
+
+        Ok(addresses
+            .iter()
+            .map(|address| {
+                let balance = balances.get(address);
+
+                EligibilityEntry {
+                    address: address.to_checksum(None),
+                    eligible: balance.is_some_and(|balance| *balance >= parameters.minimum_balance),
+                    balance: balance.map(U256::to_string),
+                }
+            })
+            .collect())
+    }
+
+    /// Evaluates the eligibility rule of the airdrop for an address.
+    ///
+    /// Returns `null` if the airdrop has no eligibility rule.
+    async fn check_rule(
+        &self,
+        address: String,
+        api_token: String,
+    ) -> async_graphql::Result<Option<RuleEvaluation>> {
+        let address = parse_address(&address)?;
+        let parameters = self.0.runtime.application_parameters();
+
+        let Some(rule) = &parameters.rule else {
+            return Ok(None);
+        };
+
+        self.0
+            .evaluate_rule(rule, &address, &api_token, &parameters)
+            .map(Some)
+            .map_err(|error| async_graphql::Error::new(error.to_string()))
+    }
 
+    /// Evaluates the eligibility rule of the airdrop for each of the `addresses`, returning the
+    /// evaluations in the same order.
+    ///
+    /// Returns `null` if the airdrop has no eligibility rule. The criteria are still queried
+    /// separately for each address, so this only saves the contract from querying the service
+    /// once per claim. At most [`MAX_CLAIM_BATCH_SIZE`] addresses can be checked at once.
+    async fn check_rule_batch(
+        &self,
+        addresses: Vec<String>,
+        api_token: String,
+    ) -> async_graphql::Result<Option<Vec<RuleEvaluation>>> {
+        if addresses.len() > MAX_CLAIM_BATCH_SIZE {
+            return Err(async_graphql::Error::new(format!(
+                "At most {MAX_CLAIM_BATCH_SIZE} addresses can be checked at once"
+            )));
+        }
+
+        let addresses = addresses
+            .iter()
+            .map(|address| parse_address(address))
+            .collect::<async_graphql::Result<Vec<_>>>()?;
+        let parameters = self.0.runtime.application_parameters();
+
+        let Some(rule) = &parameters.rule else {
+            return Ok(None);
+        };
+
+        addresses
+            .iter()
+            .map(|address| self.0.evaluate_rule(rule, address, &api_token, &parameters))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some)
+            .map_err(|error| async_graphql::Error::new(error.to_string()))
+    }
+}
+
+impl ApplicationService {
+    /// Queries the snapshot balance of an `address` from the oracle selected in the
+    /// `parameters`, authenticating with the `api_token` if needed.
+    fn query_snapshot_balance(
+        &self,
+        address: &Address,
+        api_token: &str,
+        parameters: &Parameters,
+    ) -> Result<U256, AirDropError> {
         let Parameters {
+            source_chain,
             snapshot_block,
-            minimum_balance,
+            snapshot_sampling,
+            asset,
+            eligibility,
             ..
-        } = self.0.runtime.application_parameters();
-
-        let query = format!(
-            "{{ \"sqlText\": \"\
-                SELECT BALANCE FROM ETHEREUM.NATIVE_WALLETS \
-                WHERE WALLET_ADDRESS = '{lowercase_address}' AND BLOCK_NUMBER <= {snapshot_block} \
-                ORDER BY BLOCK_NUMBER DESC \
-                LIMIT 1\
-                ;\
-            \" }}"
-        );
-
-        let response = self.0.runtime.http_request(
-            http::Request::post(SXT_GATEWAY_URL, query.as_bytes())
-                .with_header("Content-Type", b"application/json")
-                .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
-        );
-
-        ensure!(
-            response.status == 200,
-            async_graphql::Error::new(format!(
-                "Failed to perform Space-and-Time query. Status-code: {}",
-                response.status
-            ))
-        );
-
-        let result = serde_json::from_slice::<Vec<serde_json::Map<String, serde_json::Value>>>(
-            &response.body,
-        )
-        .map_err(|_| async_graphql::Error::new("Invalid response from Space-and-Time Gateway"))?;
-
-        ensure!(
-            result.len() <= 1,
-            async_graphql::Error::new(format!(
-                "Expected at most one query result from Space-and-Time, got {}",
-                result.len()
-            ))
-        );
-
-        if result.is_empty() {
-            Ok(false)
-        } else {
-            ensure!(
-                result[0].len() == 1,
-                async_graphql::Error::new(format!(
-                    "Expected a single result column from Space-and-Time query, got {}",
-                    result[0].len()
-                ))
-            );
-
-            let balance_string = result[0]["BALANCE"].as_str().ok_or_else(|| {
-                async_graphql::Error::new(format!("Query result is not a string: {result:?}"))
-            })?;
-
-            let balance = U256::from_str(balance_string).map_err(|_| {
-                async_graphql::Error::new(format!(
-                    "Query result string is not a valid balance value: {balance_string:?}"
-                ))
-            })?;
-
-            Ok(balance >= minimum_balance)
+        } = parameters;
+
+        let http = |request| self.runtime.http_request(request);
+        let sampling = snapshot_sampling.as_ref();
+
+        match eligibility {
+            EligibilitySource::JsonRpc { url } => {
+                let mut oracle = JsonRpcOracle::new(url, asset, *snapshot_block, http);
+                snapshot_balance(&mut oracle, address, asset, *snapshot_block, sampling)
+            }
+            _ => {
+                let mut oracle =
+                    SpaceAndTimeOracle::new(*source_chain, asset, *snapshot_block, api_token, http);
+                snapshot_balance(&mut oracle, address, asset, *snapshot_block, sampling)
+            }
         }
     }
+
+    /// Queries the snapshot balances of the `addresses` from the oracle selected in the
+    /// `parameters`, authenticating with the `api_token` if needed.
+    ///
+    /// Addresses that aren't part of the snapshot are missing from the result. Space-and-Time is
+    /// queried for all addresses at once, while JSON-RPC endpoints are queried for each address.
+    fn query_snapshot_balances(
+        &self,
+        addresses: &[Address],
+        api_token: &str,
+        parameters: &Parameters,
+    ) -> Result<BTreeMap<Address, U256>, AirDropError> {
+        let mut unique_addresses = addresses.to_vec();
+
+        unique_addresses.sort_unstable();
+        unique_addresses.dedup();
+
+        match &parameters.eligibility {
+            EligibilitySource::JsonRpc { .. } => {
+                let mut balances = BTreeMap::new();
+
+                for address in unique_addresses {
+                    match self.query_snapshot_balance(&address, api_token, parameters) {
+                        Ok(balance) => {
+                            balances.insert(address, balance);
+                        }
+                        Err(AirDropError::NotEligible) => {}
+                        Err(error) => return Err(error),
+                    }
+                }
+
+                Ok(balances)
+            }
+            _ => self.query_space_and_time_balances(&unique_addresses, api_token, parameters),
+        }
+    }
+
+    /// Queries the snapshot balances of the `addresses` from Space-and-Time, sampling them at
+    /// each block of the snapshot sampling in the `parameters`, if there is one.
+    fn query_space_and_time_balances(
+        &self,
+        addresses: &[Address],
+        api_token: &str,
+        parameters: &Parameters,
+    ) -> Result<BTreeMap<Address, U256>, AirDropError> {
+        let Parameters {
+            source_chain,
+            snapshot_block,
+            snapshot_sampling,
+            asset,
+            ..
+        } = parameters;
+
+        let http = |request| self.runtime.http_request(request);
+        let mut oracle =
+            SpaceAndTimeOracle::new(*source_chain, asset, *snapshot_block, api_token, http);
+
+        let Some(sampling) = snapshot_sampling else {
+            return oracle.balances_at(addresses, asset, *snapshot_block);
+        };
+
+        let blocks = sampling
+            .blocks(*snapshot_block)
+            .ok_or(AirDropError::InvalidSnapshotSampling)?;
+        let sampled_balances = blocks
+            .iter()
+            .map(|block| oracle.balances_at(addresses, asset, *block))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut balances = BTreeMap::new();
+
+        for address in addresses {
+            let samples = sampled_balances
+                .iter()
+                .map(|block_balances| block_balances.get(address).copied())
+                .collect::<Vec<_>>();
+
+            match sampling.aggregate(&blocks, &samples) {
+                Ok(balance) => {
+                    balances.insert(*address, balance);
+                }
+                Err(AirDropError::NotEligible) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(balances)
+    }
+
+    /// Evaluates the `rule` for an `address`, querying its criteria from the JSON-RPC endpoint if
+    /// it is the eligibility source in the `parameters`, or from Space-and-Time otherwise.
+    fn evaluate_rule(
+        &self,
+        rule: &EligibilityRule,
+        address: &Address,
+        api_token: &str,
+        parameters: &Parameters,
+    ) -> Result<RuleEvaluation, AirDropError> {
+        let Parameters {
+            source_chain,
+            snapshot_block,
+            asset,
+            eligibility,
+            ..
+        } = parameters;
+
+        let http = |request| self.runtime.http_request(request);
+
+        match eligibility {
+            EligibilitySource::JsonRpc { url } => {
+                let mut oracle = JsonRpcOracle::new(url, asset, *snapshot_block, http);
+                rule.evaluate(address, *snapshot_block, &mut oracle)
+            }
+            _ => {
+                let mut oracle =
+                    SpaceAndTimeOracle::new(*source_chain, asset, *snapshot_block, api_token, http);
+                rule.evaluate(address, *snapshot_block, &mut oracle)
+            }
+        }
+    }
+}
+
+/// Aggregate statistics of the airdrop campaign.
+#[derive(async_graphql::SimpleObject)]
+pub struct Statistics {
+    /// The number of airdrops that have been paid.
+    claims_paid: u64,
+    /// The total [`Amount`] of tokens that have been paid.
+    total_distributed: Amount,
+    /// The number of distinct chains that tokens have been paid to.
+    destination_chains: u64,
+    /// The number of approved airdrops that were ignored because they had already been paid.
+    rejected_replays: u64,
+    /// The number of approved airdrops that were ignored because their claims had been revoked.
+    rejected_revoked_claims: u64,
+}
+
+/// A [`ClaimRecord`] in the history of paid airdrops.
+#[derive(async_graphql::SimpleObject)]
+pub struct ClaimRecordEntry {
+    /// The claimer's address, with its EIP-55 checksum.
+    address: String,
+    record: ClaimRecord,
+}
+
+/// The result of checking if an address is eligible to claim an airdrop.
+#[derive(async_graphql::SimpleObject)]
+pub struct EligibilityEntry {
+    /// The checked address, with its EIP-55 checksum.
+    address: String,
+    /// If the address is eligible to claim an airdrop.
+    eligible: bool,
+    /// The address's snapshot balance, or `null` if it isn't part of the snapshot.
+    balance: Option<String>,
 }
 
 /// Root type that defines all the GraphQL mutations available from the service.
@@ -133,24 +480,116 @@ pub struct Mutation;
 #[async_graphql::Object]
 impl Mutation {
     /// Claims an airdrop.
+    ///
+    /// Claims submitted by a relayer may include a `relayerFee` signed by the claimer, which is
+    /// deducted from the airdrop and paid to the relayer.
+    #[allow(clippy::too_many_arguments)]
     async fn air_drop_claim(
         &self,
         destination: fungible::Account,
+        deadline: Timestamp,
+        nonce: u64,
         signature: String,
         api_token: String,
+        merkle_proof: Option<MerkleProof>,
+        relayer_fee: Option<RelayerFee>,
     ) -> async_graphql::Result<Vec<u8>> {
         let signature = signature
             .parse()
             .map_err(|_| async_graphql::Error::new("Signature could not be parsed"))?;
 
-        Ok(bcs::to_bytes(&AirDropClaim {
+        Ok(bcs::to_bytes(&Operation::Claim(AirDropClaim {
             signature,
             destination,
+            deadline,
+            nonce,
             api_token,
-        })
-        .expect("`AirDropClaim` should be serializable"))
+            merkle_proof,
+            relayer_fee,
+        }))
+        .expect("`Operation` should be serializable"))
+    }
+
+    /// Claims the airdrops of several claimers at once.
+    async fn air_drop_claim_batch(&self, claims: Vec<AirDropClaim>) -> Vec<u8> {
+        bcs::to_bytes(&Operation::ClaimBatch(claims)).expect("`Operation` should be serializable")
+    }
+
+    /// Revokes the signed airdrop claims with a nonce lower than `nonce`.
+    async fn revoke_claims(&self, signature: String, nonce: u64) -> async_graphql::Result<Vec<u8>> {
+        let signature = signature
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Signature could not be parsed"))?;
+
+        Ok(bcs::to_bytes(&Operation::RevokeClaims(ClaimRevocation {
+            signature,
+            nonce,
+        }))
+        .expect("`Operation` should be serializable"))
+    }
+
+    /// Sweeps the tokens that were not claimed to a `target` account.
+    async fn sweep(&self, target: fungible::Account) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Sweep { target }).expect("`Operation` should be serializable")
+    }
+
+    /// Pauses the airdrop.
+    async fn pause(&self) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Pause).expect("`Operation` should be serializable")
+    }
+
+    /// Resumes the airdrop.
+    async fn resume(&self) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Resume).expect("`Operation` should be serializable")
+    }
+
+    /// Funds the shards with their budgets.
+    async fn fund_shards(&self) -> Vec<u8> {
+        bcs::to_bytes(&Operation::FundShards).expect("`Operation` should be serializable")
+    }
+
+    /// Requests a `shard` to return its surplus tokens to the creator chain.
+    async fn rebalance(&self, shard: ChainId) -> Vec<u8> {
+        bcs::to_bytes(&Operation::Rebalance { shard }).expect("`Operation` should be serializable")
+    }
+}
+
+/// Returns the snapshot balance of an `address` using the `oracle`, combining the balances at
+/// several blocks if the airdrop has a snapshot `sampling`.
+fn snapshot_balance(
+    oracle: &mut (impl EligibilityOracle + RuleOracle),
+    address: &Address,
+    asset: &SnapshotAsset,
+    snapshot_block: u64,
+    sampling: Option<&SnapshotSampling>,
+) -> Result<U256, AirDropError> {
+    match sampling {
+        None => oracle.snapshot_balance(address),
+        Some(sampling) => sampling.sampled_balance(address, asset, snapshot_block, oracle),
     }
 }
 
-/// The URL of the Space-and-Time Gateway API.
-const SXT_GATEWAY_URL: &str = "https://api.spaceandtime.dev/v1/sql";
+/// Checks that the eligibility source in the `parameters` is queried by the service, instead of
+/// being verified by the contract.
+fn check_eligibility_is_queried(parameters: &Parameters) -> async_graphql::Result<()> {
+    match parameters.eligibility {
+        EligibilitySource::SpaceAndTime | EligibilitySource::JsonRpc { .. } => Ok(()),
+        EligibilitySource::Allowlist(_) | EligibilitySource::MerkleRoot(_) => {
+            Err(async_graphql::Error::new(
+                "Eligibility is verified by the contract using the evidence in the claim",
+            ))
+        }
+    }
+}
+
+/// Parses an Ethereum [`Address`] received in a query.
+fn parse_address(address: &str) -> async_graphql::Result<Address> {
+    Address::from_str(address)
+        .map_err(|_| async_graphql::Error::new(format!("Invalid address: {address:?}")))
+}
+
+/// The maximum number of addresses checked by a single `checkEligibilityBatch` query.
+const MAX_ELIGIBILITY_BATCH_SIZE: usize = 5_000;
+
+/// The maximum number of records returned in a page of the claim history.
+const MAX_CLAIM_RECORDS_PAGE_SIZE: usize = 100;
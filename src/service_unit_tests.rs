@@ -1,26 +1,32 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use airdrop_demo::{
     test_utils::{create_dummy_application_id, create_dummy_token_id, sign_claim},
-    AirDropClaim, Parameters,
+    AirDropClaim, AirDropId, AllocationPolicy, BalanceAggregation, EligibilityRule,
+    EligibilitySource, EvmChain, Operation, Parameters, SampledBlocks, SnapshotAsset,
+    SnapshotSampling, MAX_ADDRESSES_PER_QUERY, SXT_GATEWAY_URL,
 };
 use alloy_primitives::{Address, U256};
 use k256::ecdsa::SigningKey;
 use linera_sdk::{
     abis::fungible,
     bcs, http,
-    linera_base_types::{AccountOwner, ChainId, CryptoHash},
+    linera_base_types::{AccountOwner, Amount, BlockHeight, ChainId, CryptoHash, Timestamp},
     serde_json,
     service::MockServiceRuntime,
     util::BlockingWait,
+    views::{RootView, View, ViewStorageContext},
     Service,
 };
 use rand::rngs::OsRng;
 
-use super::{ApplicationService, SXT_GATEWAY_URL};
+use super::{
+    state::{Application, ClaimRecord, ClaimStatus},
+    ApplicationService,
+};
 
 /// Tests if a GraphQL query can successfully check if an account is eligible.
 #[test]
@@ -39,7 +45,10 @@ fn query_returns_address_is_eligible() {
 
     let response = service.handle_query(eligibility_query).blocking_wait();
 
-    assert!(extract_eligibility_from(response));
+    assert_eq!(
+        extract_eligibility_from(response),
+        Some(U256::from(MINIMUM_BALANCE))
+    );
 }
 
 /// Tests if a GraphQL query can deny an account's eligibility if it's balance was below the
@@ -62,7 +71,7 @@ fn query_returns_address_with_insufficient_balance_is_not_eligible() {
 
     let response = service.handle_query(eligibility_query).blocking_wait();
 
-    assert!(!extract_eligibility_from(response));
+    assert_eq!(extract_eligibility_from(response), None);
 }
 
 /// Tests if a GraphQL query can deny an account's eligibility if it didn't exist at the snapshot
@@ -83,7 +92,7 @@ fn query_returns_address_thats_unknown_at_snapshot_is_not_eligible() {
 
     let response = service.handle_query(eligibility_query).blocking_wait();
 
-    assert!(!extract_eligibility_from(response));
+    assert_eq!(extract_eligibility_from(response), None);
 }
 
 /// Tests if a GraphQL query reports query errors.
@@ -107,6 +116,657 @@ fn query_returns_http_errors() {
     assert_eq!(response.errors.len(), 1);
 }
 
+/// Tests if a GraphQL query checks the eligibility of several addresses with a single
+/// Space-and-Time query, reporting the balance of each address.
+#[test]
+fn query_checks_eligibility_of_address_batch() {
+    let mut service = create_service();
+
+    let eligible = Address::repeat_byte(0x01);
+    let insufficient = Address::repeat_byte(0x02);
+    let unknown = Address::repeat_byte(0x03);
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let conditions = format!(
+        "WHERE WALLET_ADDRESS IN ('0x{0}', '0x{1}', '0x{2}') AND BLOCK_NUMBER <= 100",
+        hex::encode(eligible.as_slice()),
+        hex::encode(insufficient.as_slice()),
+        hex::encode(unknown.as_slice()),
+    );
+    let sql_query = latest_native_balances_query(&conditions);
+    let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+    let query_response = serde_json::json!([
+        { "WALLET_ADDRESS": format!("0x{}", hex::encode(eligible.as_slice())), "BALANCE": "50" },
+        { "WALLET_ADDRESS": format!("0x{}", hex::encode(insufficient.as_slice())), "BALANCE": "3" },
+    ]);
+
+    runtime.add_expected_http_request(
+        http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+            .with_header("Content-Type", b"application/json")
+            .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+        http::Response::ok(query_response.to_string().as_bytes()),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{
+            checkEligibilityBatch(
+                addresses: ["{unknown}", "{eligible}", "{insufficient}", "{eligible}"],
+                apiToken: "{api_token}"
+            ) {{ address eligible balance }}
+        }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let data = response.data.into_json().expect("Invalid JSON response");
+
+    assert_eq!(
+        data["checkEligibilityBatch"],
+        serde_json::json!([
+            {
+                "address": unknown.to_checksum(None),
+                "eligible": false,
+                "balance": null,
+            },
+            {
+                "address": eligible.to_checksum(None),
+                "eligible": true,
+                "balance": "50",
+            },
+            {
+                "address": insufficient.to_checksum(None),
+                "eligible": false,
+                "balance": "3",
+            },
+            {
+                "address": eligible.to_checksum(None),
+                "eligible": true,
+                "balance": "50",
+            },
+        ])
+    );
+}
+
+/// Tests if a GraphQL query splits a large batch of addresses into several Space-and-Time
+/// queries.
+#[test]
+fn query_checks_eligibility_of_address_batch_in_chunks() {
+    let mut service = create_service();
+
+    let mut addresses = (0..MAX_ADDRESSES_PER_QUERY + 1)
+        .map(|_| Address::random())
+        .collect::<Vec<_>>();
+    addresses.sort();
+
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    for chunk in addresses.chunks(MAX_ADDRESSES_PER_QUERY) {
+        let address_list = chunk
+            .iter()
+            .map(|address| format!("'0x{}'", hex::encode(address.as_slice())))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql_query = latest_native_balances_query(&format!(
+            "WHERE WALLET_ADDRESS IN ({address_list}) AND BLOCK_NUMBER <= 100"
+        ));
+        let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+        let query_response = serde_json::json!([
+            { "WALLET_ADDRESS": format!("0x{}", hex::encode(chunk[0].as_slice())), "BALANCE": 10 },
+        ]);
+
+        runtime.add_expected_http_request(
+            http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+                .with_header("Content-Type", b"application/json")
+                .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+            http::Response::ok(query_response.to_string().as_bytes()),
+        );
+    }
+
+    let address_list = addresses
+        .iter()
+        .map(|address| format!("\"{address}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = async_graphql::Request::new(format!(
+        r#"query {{
+            checkEligibilityBatch(addresses: [{address_list}], apiToken: "{api_token}") {{
+                eligible
+            }}
+        }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+    let data = response.data.into_json().expect("Invalid JSON response");
+    let eligible_count = data["checkEligibilityBatch"]
+        .as_array()
+        .expect("Batch result is not a list")
+        .iter()
+        .filter(|entry| entry["eligible"] == serde_json::Value::Bool(true))
+        .count();
+
+    assert_eq!(eligible_count, 2);
+}
+
+/// Tests if a GraphQL query rejects a Space-and-Time response with more than one latest balance
+/// of the same address.
+#[test]
+fn query_rejects_duplicate_balances_in_address_batch() {
+    let mut service = create_service();
+
+    let address = Address::repeat_byte(0x01);
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let sql_query = latest_native_balances_query(&format!(
+        "WHERE WALLET_ADDRESS IN ('0x{}') AND BLOCK_NUMBER <= 100",
+        hex::encode(address.as_slice())
+    ));
+    let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+    let query_response = serde_json::json!([
+        { "WALLET_ADDRESS": format!("0x{}", hex::encode(address.as_slice())), "BALANCE": "50" },
+        { "WALLET_ADDRESS": format!("0x{}", hex::encode(address.as_slice())), "BALANCE": "1" },
+    ]);
+
+    runtime.add_expected_http_request(
+        http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+            .with_header("Content-Type", b"application/json")
+            .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+        http::Response::ok(query_response.to_string().as_bytes()),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{
+            checkEligibilityBatch(addresses: ["{address}"], apiToken: "{api_token}") {{
+                eligible
+            }}
+        }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0]
+        .message
+        .contains("Space-and-Time returned more than one balance of wallet"));
+}
+
+/// Tests if a GraphQL query rejects a crafted address before querying Space-and-Time.
+#[test]
+fn query_rejects_crafted_address() {
+    let service = create_service();
+
+    let query = async_graphql::Request::new(
+        r#"query {
+            checkEligibility(
+                address: "0x0000000000000000000000000000000000000000' OR '1' = '1",
+                apiToken: "API token"
+            )
+        }"#,
+    );
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert!(matches!(response.data, async_graphql::Value::Null));
+    assert_eq!(response.errors.len(), 1);
+    assert!(response.errors[0].message.starts_with("Invalid address"));
+}
+
+/// Tests if a GraphQL query can check if an account is eligible using an Ethereum JSON-RPC
+/// endpoint.
+#[test]
+fn query_checks_eligibility_with_json_rpc() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let url = "https://rpc.example.com";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.eligibility = EligibilitySource::JsonRpc {
+        url: url.to_owned(),
+    };
+    runtime.set_application_parameters(parameters);
+
+    let expected_request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_getBalance",
+        "params": [format!("0x{}", hex::encode(address.as_slice())), "0x64"],
+    });
+
+    runtime.add_expected_http_request(
+        http::Request::post(url, expected_request.to_string().as_bytes())
+            .with_header("Content-Type", b"application/json"),
+        http::Response::ok(br#"{ "jsonrpc": "2.0", "id": 1, "result": "0x2a" }"#),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(42)));
+}
+
+/// Tests if a GraphQL query samples the balance at several blocks and uses the lowest one.
+#[test]
+fn query_uses_minimum_sampled_balance() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.snapshot_sampling = Some(SnapshotSampling {
+        blocks: SampledBlocks::List(vec![90, 80]),
+        aggregation: BalanceAggregation::Minimum,
+    });
+    runtime.set_application_parameters(parameters);
+
+    for (block, balance) in [(80, "50"), (90, "20"), (100, "50")] {
+        let sql_query = format!(
+            "SELECT BALANCE FROM ETHEREUM.NATIVE_WALLETS \
+            WHERE WALLET_ADDRESS = '0x{}' AND BLOCK_NUMBER <= {block} \
+            ORDER BY BLOCK_NUMBER DESC \
+            LIMIT 1;",
+            hex::encode(address.as_slice())
+        );
+        let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+
+        runtime.add_expected_http_request(
+            http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+                .with_header("Content-Type", b"application/json")
+                .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+            http::Response::ok(format!(r#"[{{ "BALANCE": "{balance}" }}]"#).as_bytes()),
+        );
+    }
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "{api_token}") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(20)));
+}
+
+/// Tests if a GraphQL query averages the balances sampled in a block range, weighted by the
+/// number of blocks each balance was held, so that a balance borrowed for the snapshot block
+/// isn't enough to be eligible.
+#[test]
+fn query_uses_time_weighted_average_balance() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let url = "https://rpc.example.com";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.eligibility = EligibilitySource::JsonRpc {
+        url: url.to_owned(),
+    };
+    parameters.snapshot_sampling = Some(SnapshotSampling {
+        blocks: SampledBlocks::Range {
+            start: 80,
+            end: 95,
+            step: 10,
+        },
+        aggregation: BalanceAggregation::TimeWeightedAverage,
+    });
+    runtime.set_application_parameters(parameters);
+
+    for (block, balance) in [("0x50", "0x0"), ("0x5a", "0x3"), ("0x64", "0xc8")] {
+        let expected_request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getBalance",
+            "params": [format!("0x{}", hex::encode(address.as_slice())), block],
+        });
+
+        runtime.add_expected_http_request(
+            http::Request::post(url, expected_request.to_string().as_bytes())
+                .with_header("Content-Type", b"application/json"),
+            http::Response::ok(
+                format!(r#"{{ "jsonrpc": "2.0", "id": 1, "result": "{balance}" }}"#).as_bytes(),
+            ),
+        );
+    }
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    // (0 * 10 + 3 * 10 + 200 * 1) / 21 = 10
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(10)));
+}
+
+/// Tests if a GraphQL query checks the ERC-20 token balance of an account when the airdrop
+/// targets the holders of a token.
+#[test]
+fn query_checks_erc20_token_balance() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let token_contract = Address::random();
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.asset = SnapshotAsset::Erc20 {
+        contract: token_contract,
+    };
+    runtime.set_application_parameters(parameters);
+
+    let sql_query = format!(
+        "SELECT BALANCE FROM ETHEREUM.FUNGIBLETOKEN_WALLETS \
+        WHERE WALLET_ADDRESS = '0x{}' AND TOKEN_ADDRESS = '0x{}' AND BLOCK_NUMBER <= 100 \
+        ORDER BY BLOCK_NUMBER DESC \
+        LIMIT 1;",
+        hex::encode(address.as_slice()),
+        hex::encode(token_contract.as_slice())
+    );
+    let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+
+    runtime.add_expected_http_request(
+        http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+            .with_header("Content-Type", b"application/json")
+            .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+        http::Response::ok(br#"[{ "BALANCE": "500" }]"#),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "{api_token}") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(500)));
+}
+
+/// Tests if a GraphQL query reads the balances from the Space-and-Time schema of the source
+/// chain, and reports the chain's ID for signing claims.
+#[test]
+fn query_uses_source_chain_schema() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let api_token = "API token";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.source_chain = EvmChain::Base;
+    runtime.set_application_parameters(parameters);
+
+    let sql_query = format!(
+        "SELECT BALANCE FROM BASE.NATIVE_WALLETS \
+        WHERE WALLET_ADDRESS = '0x{}' AND BLOCK_NUMBER <= 100 \
+        ORDER BY BLOCK_NUMBER DESC \
+        LIMIT 1;",
+        hex::encode(address.as_slice())
+    );
+    let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
+
+    runtime.add_expected_http_request(
+        http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
+            .with_header("Content-Type", b"application/json")
+            .with_header("Authorization", format!("Bearer {api_token}").as_bytes()),
+        http::Response::ok(br#"[{ "BALANCE": "500" }]"#),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "{api_token}") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(500)));
+
+    let response = service
+        .handle_query(async_graphql::Request::new("query { sourceChainId }"))
+        .blocking_wait();
+
+    let async_graphql::Value::Object(data) = response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+
+    assert_eq!(data["sourceChainId"], async_graphql::Value::from(8_453));
+}
+
+/// Tests if a GraphQL query calls `balanceOf` on an ERC-721 collection through a JSON-RPC
+/// endpoint.
+#[test]
+fn query_calls_erc721_balance_with_json_rpc() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let collection = Address::random();
+    let url = "https://rpc.example.com";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.asset = SnapshotAsset::Erc721 { collection };
+    parameters.eligibility = EligibilitySource::JsonRpc {
+        url: url.to_owned(),
+    };
+    runtime.set_application_parameters(parameters);
+
+    let call_data = format!(
+        "0x70a08231000000000000000000000000{}",
+        hex::encode(address.as_slice())
+    );
+    let expected_request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_call",
+        "params": [
+            {
+                "to": format!("0x{}", hex::encode(collection.as_slice())),
+                "data": call_data,
+            },
+            "0x64",
+        ],
+    });
+
+    runtime.add_expected_http_request(
+        http::Request::post(url, expected_request.to_string().as_bytes())
+            .with_header("Content-Type", b"application/json"),
+        http::Response::ok(
+            format!(
+                r#"{{ "jsonrpc": "2.0", "id": 1, "result": "0x{:064x}" }}"#,
+                12
+            )
+            .as_bytes(),
+        ),
+    );
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkEligibility(address: "{address}", apiToken: "") }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(extract_eligibility_from(response), Some(U256::from(12)));
+}
+
+/// Tests if a GraphQL query evaluates a composite eligibility rule, querying each criterion from a
+/// JSON-RPC endpoint until the result is known.
+#[test]
+fn query_evaluates_composite_rule() {
+    let mut service = create_service();
+
+    let address = Address::random();
+    let collection = Address::random();
+    let url = "https://rpc.example.com";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.eligibility = EligibilitySource::JsonRpc {
+        url: url.to_owned(),
+    };
+    parameters.rule = Some(EligibilityRule::All(vec![
+        EligibilityRule::Holds {
+            asset: SnapshotAsset::Native,
+            minimum: U256::from(10),
+        },
+        EligibilityRule::Any(vec![
+            EligibilityRule::Holds {
+                asset: SnapshotAsset::Erc721 { collection },
+                minimum: U256::from(1),
+            },
+            EligibilityRule::Transactions {
+                minimum: 5,
+                before_block: 50,
+            },
+        ]),
+    ]));
+    runtime.set_application_parameters(parameters);
+
+    let hex_address = format!("0x{}", hex::encode(address.as_slice()));
+    let expected_calls = [
+        (
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_getBalance",
+                "params": [hex_address, "0x64"],
+            }),
+            "0x14",
+        ),
+        (
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_call",
+                "params": [
+                    {
+                        "to": format!("0x{}", hex::encode(collection.as_slice())),
+                        "data": format!("0x70a08231000000000000000000000000{}", &hex_address[2..]),
+                    },
+                    "0x64",
+                ],
+            }),
+            "0x0",
+        ),
+        (
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "eth_getTransactionCount",
+                "params": [hex_address, "0x31"],
+            }),
+            "0x7",
+        ),
+    ];
+
+    for (request, result) in expected_calls {
+        runtime.add_expected_http_request(
+            http::Request::post(url, request.to_string().as_bytes())
+                .with_header("Content-Type", b"application/json"),
+            http::Response::ok(
+                format!(r#"{{ "jsonrpc": "2.0", "id": 1, "result": "{result}" }}"#).as_bytes(),
+            ),
+        );
+    }
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{ checkRule(address: "{address}", apiToken: "") {{ satisfied explanation }} }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    let async_graphql::Value::Object(data) = response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+    let async_graphql::Value::Object(evaluation) = &data["checkRule"] else {
+        panic!("Unexpected `checkRule` result: {data:?}");
+    };
+    let async_graphql::Value::String(explanation) = &evaluation["explanation"] else {
+        panic!("Unexpected `checkRule` explanation: {evaluation:?}");
+    };
+
+    assert_eq!(evaluation["satisfied"], async_graphql::Value::Boolean(true));
+    assert!(explanation.contains("no: held 0 of Erc721"));
+    assert!(explanation.contains("yes: sent 7 transactions before block 50"));
+}
+
+/// Tests if a GraphQL query evaluates the eligibility rule for several addresses at once,
+/// returning the evaluations in the order of the addresses.
+#[test]
+fn query_evaluates_rule_for_address_batch() {
+    let mut service = create_service();
+
+    let addresses = [Address::repeat_byte(0x01), Address::repeat_byte(0x02)];
+    let url = "https://rpc.example.com";
+    let runtime = Arc::get_mut(&mut service.runtime)
+        .expect("Service should not have an active clone when preparing the query");
+
+    let mut parameters = runtime.application_parameters();
+    parameters.eligibility = EligibilitySource::JsonRpc {
+        url: url.to_owned(),
+    };
+    parameters.rule = Some(EligibilityRule::Transactions {
+        minimum: 5,
+        before_block: 50,
+    });
+    runtime.set_application_parameters(parameters);
+
+    for (address, transaction_count) in addresses.iter().zip(["0x7", "0x2"]) {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getTransactionCount",
+            "params": [format!("0x{}", hex::encode(address.as_slice())), "0x31"],
+        });
+
+        runtime.add_expected_http_request(
+            http::Request::post(url, request.to_string().as_bytes())
+                .with_header("Content-Type", b"application/json"),
+            http::Response::ok(
+                format!(r#"{{ "jsonrpc": "2.0", "id": 1, "result": "{transaction_count}" }}"#)
+                    .as_bytes(),
+            ),
+        );
+    }
+
+    let query = async_graphql::Request::new(format!(
+        r#"query {{
+            checkRuleBatch(addresses: ["{}", "{}"], apiToken: "") {{ satisfied }}
+        }}"#,
+        addresses[0], addresses[1]
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    assert_eq!(
+        response.data.into_json().expect("Invalid JSON response"),
+        serde_json::json!({
+            "checkRuleBatch": [{ "satisfied": true }, { "satisfied": false }],
+        })
+    );
+}
+
 /// Tests if a GraphQL mutation can be used to create an [`AirDropClaim`] operation.
 #[test]
 fn mutation_generates_air_drop_claim() {
@@ -122,7 +782,13 @@ fn mutation_generates_air_drop_claim() {
     let api_token = "API token".to_owned();
     let application_id = create_dummy_application_id("zk-airdrop");
     let signing_key = SigningKey::random(&mut OsRng);
-    let signature = sign_claim(&signing_key, application_id, destination);
+    let signature = sign_claim(
+        &signing_key,
+        application_id,
+        destination,
+        Timestamp::from(2_000),
+        7,
+    );
     let signature_string = hex::encode(signature.as_bytes());
 
     let json_query = format!(
@@ -134,6 +800,8 @@ fn mutation_generates_air_drop_claim() {
                         chainId: \\\"{chain_id}\\\", \
                         owner: \\\"{claimer}\\\" \
                     }}, \
+                    deadline: 2000, \
+                    nonce: 7, \
                     apiToken: \\\"{api_token}\\\" \
                 ) \
             }}\"
@@ -144,30 +812,10 @@ fn mutation_generates_air_drop_claim() {
 
     let response = service.handle_query(query).blocking_wait();
 
-    let async_graphql::Value::Object(response_object) = response.data else {
-        panic!("Unexpected response data from query: {response:?}");
-    };
-    let async_graphql::Value::List(ref claim_bytes) = response_object["airDropClaim"] else {
-        panic!("Missing serialized `airDropClaim` in response object");
+    let Operation::Claim(mut operation) = extract_operation_from(response, "airDropClaim") else {
+        panic!("Returned operation is not an `AirDropClaim`");
     };
 
-    let serialized_operation = claim_bytes
-        .iter()
-        .map(|wrapped_byte| {
-            let async_graphql::Value::Number(byte_value) = wrapped_byte else {
-                panic!("Serialized `airDropClaim` is not a list of numbers");
-            };
-            let byte_integer = byte_value
-                .as_u64()
-                .expect("Serialized `airDropClaim` is not a list of integers");
-
-            u8::try_from(byte_integer).expect("Serialized `airDropClaim` is not a list of bytes")
-        })
-        .collect::<Vec<u8>>();
-
-    let mut operation = bcs::from_bytes::<AirDropClaim>(&serialized_operation)
-        .expect("Failed to deserialize returned operation");
-
     operation.signature = operation.signature.with_parity(operation.signature.v());
 
     let expected_operation = AirDropClaim {
@@ -176,25 +824,347 @@ fn mutation_generates_air_drop_claim() {
             chain_id,
             owner: claimer,
         },
+        deadline: Timestamp::from(2_000),
+        nonce: 7,
         api_token,
+        merkle_proof: None,
+        relayer_fee: None,
     };
 
     assert_eq!(operation, expected_operation);
 }
 
+/// Tests if a GraphQL mutation rejects an [`AirDropClaim`] with a field it doesn't know, instead
+/// of silently ignoring it.
+#[test]
+fn mutation_rejects_air_drop_claim_with_unknown_field() {
+    let service = create_service();
+
+    let chain_id = ChainId(CryptoHash::test_hash("chain ID"));
+    let claimer = AccountOwner::Address32(CryptoHash::test_hash("claimer"));
+    let signature = sign_claim(
+        &SigningKey::random(&mut OsRng),
+        create_dummy_application_id("zk-airdrop"),
+        fungible::Account {
+            chain_id,
+            owner: claimer,
+        },
+        Timestamp::from(2_000),
+        7,
+    );
+    let signature_string = hex::encode(signature.as_bytes());
+
+    let query = async_graphql::Request::new(format!(
+        r#"mutation {{
+            airDropClaimBatch(claims: [{{
+                signature: "{signature_string}",
+                destination: {{ chainId: "{chain_id}", owner: "{claimer}" }},
+                deadline: 2000,
+                nonce: 7,
+                apiToken: "API token",
+                relayerFees: null
+            }}])
+        }}"#
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(response.errors.len(), 1);
+    assert!(
+        response.errors[0]
+            .message
+            .contains("`AirDropClaim` object has an unknown field `relayerFees`"),
+        "{:?}",
+        response.errors
+    );
+}
+
+/// Tests if a GraphQL mutation can be used to create a `Sweep` operation.
+#[test]
+fn mutation_generates_sweep() {
+    let service = create_service();
+
+    let target = fungible::Account {
+        chain_id: ChainId(CryptoHash::test_hash("chain ID")),
+        owner: AccountOwner::Address32(CryptoHash::test_hash("treasury")),
+    };
+
+    let query = async_graphql::Request::new(format!(
+        "mutation {{ sweep(target: {{ chainId: \"{}\", owner: \"{}\" }}) }}",
+        target.chain_id, target.owner
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(
+        extract_operation_from(response, "sweep"),
+        Operation::Sweep { target }
+    );
+}
+
+/// Tests if a GraphQL mutation can be used to create a `Rebalance` operation.
+#[test]
+fn mutation_generates_rebalance() {
+    let service = create_service();
+    let shard = ChainId(CryptoHash::test_hash("shard"));
+
+    let query =
+        async_graphql::Request::new(format!("mutation {{ rebalance(shard: \"{shard}\") }}"));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(
+        extract_operation_from(response, "rebalance"),
+        Operation::Rebalance { shard }
+    );
+}
+
+/// Tests if a GraphQL query returns the budget that hasn't been distributed yet.
+#[test]
+fn query_returns_remaining_budget() {
+    let service = create_service_with_state(|state| {
+        state.distributed_total.set(Amount::from_tokens(40));
+    });
+
+    let query = async_graphql::Request::new("query { remainingBudget }");
+
+    let response = service.handle_query(query).blocking_wait();
+
+    let async_graphql::Value::Object(data) = response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+
+    assert_eq!(
+        data["remainingBudget"],
+        async_graphql::Value::String(Amount::from_tokens(BUDGET_TOKENS - 40).to_string())
+    );
+}
+
+/// Tests if a GraphQL query returns whether the airdrop is paused.
+#[test]
+fn query_returns_paused_flag() {
+    let service = create_service_with_state(|state| {
+        state.paused.set(true);
+    });
+
+    let query = async_graphql::Request::new("query { paused }");
+
+    let response = service.handle_query(query).blocking_wait();
+
+    let async_graphql::Value::Object(data) = response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+
+    assert_eq!(data["paused"], async_graphql::Value::Boolean(true));
+}
+
+/// Tests if a GraphQL query returns the record of a paid airdrop.
+#[test]
+fn query_returns_claim_record() {
+    let address = Address::random();
+    let record = create_dummy_claim_record(0);
+    let stored_record = record.clone();
+
+    let service = create_service_with_state(move |state| {
+        state
+            .handled_airdrops
+            .insert(&AirDropId::from(address), stored_record)
+            .expect("Failed to write claim record to mock storage");
+    });
+
+    let query = async_graphql::Request::new(format!(
+        "query {{ \
+            paid: claimRecord(address: \"{address}\") {{ status amount blockHeight timestamp }} \
+            unpaid: claimRecord(address: \"{}\") {{ status }} \
+        }}",
+        Address::random()
+    ));
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(
+        response.data.into_json().expect("Invalid JSON response"),
+        serde_json::json!({
+            "paid": {
+                "status": "PAID",
+                "amount": record.amount.to_string(),
+                "blockHeight": record.block_height.0,
+                "timestamp": record.timestamp.micros(),
+            },
+            "unpaid": null,
+        })
+    );
+}
+
+/// Tests if a GraphQL query returns the claim history in pages.
+#[test]
+fn query_returns_claim_records_in_pages() {
+    let addresses = (0..5).map(|_| Address::random()).collect::<Vec<_>>();
+    let stored_addresses = addresses.clone();
+
+    let service = create_service_with_state(move |state| {
+        for (index, address) in stored_addresses.into_iter().enumerate() {
+            state
+                .handled_airdrops
+                .insert(&AirDropId::from(address), create_dummy_claim_record(index))
+                .expect("Failed to write claim record to mock storage");
+        }
+    });
+
+    let query = async_graphql::Request::new(
+        "query { \
+            first: claimRecords(limit: 2) { address } \
+            rest: claimRecords(offset: 2) { address } \
+        }",
+    );
+
+    let response = service.handle_query(query).blocking_wait();
+
+    let data = response.data.into_json().expect("Invalid JSON response");
+    let extract_addresses = |page: &serde_json::Value| {
+        page.as_array()
+            .expect("Claim records page is not a list")
+            .iter()
+            .map(|entry| {
+                entry["address"]
+                    .as_str()
+                    .expect("Claim record address is not a string")
+                    .parse::<Address>()
+                    .expect("Invalid claim record address")
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let first_page = extract_addresses(&data["first"]);
+    let rest = extract_addresses(&data["rest"]);
+
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(rest.len(), 3);
+
+    let mut all_addresses = [first_page, rest].concat();
+    let mut expected_addresses = addresses;
+
+    all_addresses.sort();
+    expected_addresses.sort();
+
+    assert_eq!(all_addresses, expected_addresses);
+}
+
+/// Tests if a GraphQL query returns the campaign statistics.
+#[test]
+fn query_returns_stats() {
+    let service = create_service_with_state(|state| {
+        state.claims_paid.set(4);
+        state.distributed_total.set(Amount::from_tokens(12));
+        state.destination_chain_count.set(3);
+        state.rejected_replays.set(2);
+        state.rejected_revoked_claims.set(1);
+    });
+
+    let query = async_graphql::Request::new(
+        "query { stats { \
+            claimsPaid totalDistributed destinationChains rejectedReplays rejectedRevokedClaims \
+        } }",
+    );
+
+    let response = service.handle_query(query).blocking_wait();
+
+    assert_eq!(
+        response.data.into_json().expect("Invalid JSON response"),
+        serde_json::json!({
+            "stats": {
+                "claimsPaid": 4,
+                "totalDistributed": Amount::from_tokens(12).to_string(),
+                "destinationChains": 3,
+                "rejectedReplays": 2,
+                "rejectedRevokedClaims": 1,
+            },
+        })
+    );
+}
+
+/// Tests if a GraphQL query returns the claim window.
+#[test]
+fn query_returns_claim_window() {
+    let service = create_service();
+
+    let query = async_graphql::Request::new("query { claimStart claimEnd }");
+
+    let response = service.handle_query(query).blocking_wait();
+
+    let async_graphql::Value::Object(data) = response.data else {
+        panic!("Unexpected response data: {response:?}");
+    };
+
+    assert_eq!(
+        data["claimStart"],
+        async_graphql::Value::from(Timestamp::from(CLAIM_START).micros())
+    );
+    assert_eq!(
+        data["claimEnd"],
+        async_graphql::Value::from(Timestamp::from(CLAIM_END).micros())
+    );
+}
+
 /// Creates an [`ApplicationService`] instance.
 fn create_service() -> ApplicationService {
-    let runtime = MockServiceRuntime::new().with_application_parameters(Parameters {
-        token_id: create_dummy_token_id(),
-        snapshot_block: 100,
-        minimum_balance: U256::from(MINIMUM_BALANCE),
-    });
+    create_service_with_state(|_| {})
+}
+
+/// Creates an [`ApplicationService`] instance with its [`Application`] state prepared by the
+/// `setup` closure.
+fn create_service_with_state(setup: impl FnOnce(&mut Application)) -> ApplicationService {
+    let runtime = MockServiceRuntime::new()
+        .with_application_parameters(Parameters {
+            token_id: create_dummy_token_id(),
+            source_chain: EvmChain::Ethereum,
+            snapshot_block: 100,
+            snapshot_sampling: None,
+            minimum_balance: U256::from(MINIMUM_BALANCE),
+            asset: SnapshotAsset::Native,
+            eligibility: EligibilitySource::SpaceAndTime,
+            rule: None,
+            allocation: AllocationPolicy::Flat(Amount::ONE),
+            budget: Amount::from_tokens(BUDGET_TOKENS),
+            claim_start: Timestamp::from(CLAIM_START),
+            claim_end: Timestamp::from(CLAIM_END),
+            admin: AccountOwner::Address32(CryptoHash::test_hash("admin")),
+            shards: vec![],
+        })
+        .with_chain_id(ChainId(CryptoHash::test_hash("creator chain")));
+
+    let writable_context =
+        ViewStorageContext::new_unsafe(runtime.key_value_store().to_mut(), Vec::new(), ());
+    let mut initial_state = Application::load(writable_context)
+        .blocking_wait()
+        .expect("Failed to read from mock key value store");
+
+    setup(&mut initial_state);
+
+    initial_state
+        .save()
+        .blocking_wait()
+        .expect("Failed to write to mock key value store");
+
+    let state = Application::load(runtime.root_view_storage_context())
+        .blocking_wait()
+        .expect("Failed to read from mock key value store");
 
     ApplicationService {
+        state: Arc::new(state),
         runtime: Arc::new(runtime),
     }
 }
 
+/// The total budget of the airdrop in the tests, in tokens.
+const BUDGET_TOKENS: u128 = 100;
+
+/// The start of the claim window in the tests, in microseconds since the Unix epoch.
+const CLAIM_START: u64 = 1_000;
+
+/// The end of the claim window in the tests, in microseconds since the Unix epoch.
+const CLAIM_END: u64 = 2_000;
+
 /// The minimum balance to be eligible for an airdrop in the tests.
 const MINIMUM_BALANCE: usize = 10;
 
@@ -219,7 +1189,7 @@ fn prepare_eligibility_query(
         LIMIT 1;",
         hex::encode(address.as_slice())
     );
-    let expected_query = format!(r#"{{ "sqlText": "{sql_query}" }}"#);
+    let expected_query = serde_json::json!({ "sqlText": sql_query }).to_string();
 
     runtime.add_expected_http_request(
         http::Request::post(SXT_GATEWAY_URL, expected_query.as_bytes())
@@ -239,9 +1209,9 @@ fn prepare_eligibility_query(
     serde_json::from_str(&json_query).expect("Failed to deserialize GraphQL query")
 }
 
-/// Parses the [`async_graphql::Response`] of `checkEligibility` to extract the `true` or `false`
-/// value that indicates the eligibility.
-fn extract_eligibility_from(response: async_graphql::Response) -> bool {
+/// Parses the [`async_graphql::Response`] of `checkEligibility` to extract the snapshot balance of
+/// an eligible address, or [`None`] if the address is not eligible.
+fn extract_eligibility_from(response: async_graphql::Response) -> Option<U256> {
     assert_eq!(
         response.errors.len(),
         0,
@@ -259,9 +1229,65 @@ fn extract_eligibility_from(response: async_graphql::Response) -> bool {
         "Expected a single item in response data: {data:?}"
     );
 
-    let async_graphql::Value::Boolean(is_eligible) = data["checkEligibility"] else {
-        panic!("Unexpected `checkEligibility` result: {data:?}");
+    match &data["checkEligibility"] {
+        async_graphql::Value::String(balance) => {
+            Some(U256::from_str(balance).expect("Invalid balance in `checkEligibility` result"))
+        }
+        async_graphql::Value::Null => None,
+        _ => panic!("Unexpected `checkEligibility` result: {data:?}"),
+    }
+}
+
+/// Deserializes the [`Operation`] returned by a GraphQL mutation in the `field` of the `response`.
+fn extract_operation_from(response: async_graphql::Response, field: &str) -> Operation {
+    let async_graphql::Value::Object(response_object) = response.data else {
+        panic!("Unexpected response data from query: {response:?}");
+    };
+    let async_graphql::Value::List(ref operation_bytes) = response_object[field] else {
+        panic!("Missing serialized `{field}` in response object");
     };
 
-    is_eligible
+    let serialized_operation = operation_bytes
+        .iter()
+        .map(|wrapped_byte| {
+            let async_graphql::Value::Number(byte_value) = wrapped_byte else {
+                panic!("Serialized `{field}` is not a list of numbers");
+            };
+            let byte_integer = byte_value
+                .as_u64()
+                .unwrap_or_else(|| panic!("Serialized `{field}` is not a list of integers"));
+
+            u8::try_from(byte_integer)
+                .unwrap_or_else(|_| panic!("Serialized `{field}` is not a list of bytes"))
+        })
+        .collect::<Vec<u8>>();
+
+    bcs::from_bytes(&serialized_operation).expect("Failed to deserialize returned operation")
+}
+
+/// Returns the SQL query for the latest native balance of each wallet that matches the
+/// `conditions`.
+fn latest_native_balances_query(conditions: &str) -> String {
+    format!(
+        "SELECT WALLET_ADDRESS, BALANCE FROM ETHEREUM.NATIVE_WALLETS \
+        JOIN (SELECT WALLET_ADDRESS AS LATEST_WALLET_ADDRESS, \
+        MAX(BLOCK_NUMBER) AS LATEST_BLOCK_NUMBER \
+        FROM ETHEREUM.NATIVE_WALLETS {conditions} GROUP BY WALLET_ADDRESS) AS LATEST \
+        ON WALLET_ADDRESS = LATEST_WALLET_ADDRESS \
+        AND BLOCK_NUMBER = LATEST_BLOCK_NUMBER {conditions};"
+    )
+}
+
+/// Creates a dummy [`ClaimRecord`] to store in the claim history for testing.
+fn create_dummy_claim_record(index: usize) -> ClaimRecord {
+    ClaimRecord {
+        status: ClaimStatus::Paid,
+        amount: Amount::from_tokens(index as u128 + 1),
+        destination: fungible::Account {
+            chain_id: ChainId(CryptoHash::test_hash(format!("destination chain {index}"))),
+            owner: AccountOwner::Address32(CryptoHash::test_hash(format!("owner {index}"))),
+        },
+        block_height: BlockHeight(index as u64),
+        timestamp: Timestamp::from(CLAIM_START + index as u64),
+    }
 }
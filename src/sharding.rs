@@ -0,0 +1,47 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Distribution of the token payments across multiple payer microchains.
+//!
+//! The claimer addresses are split into ranges, and each range is assigned to a shard that pays
+//! the airdrops of the addresses in that range. Each shard keeps track of the airdrops it has paid
+//! and has its own budget and token float. Addresses that aren't covered by any shard are paid by
+//! the application's creator chain.
+
+use alloy_primitives::Address;
+use linera_sdk::linera_base_types::{Amount, ChainId};
+use serde::{Deserialize, Serialize};
+
+/// A range of claimer addresses whose airdrops are paid by a specific chain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PayerShard {
+    /// The first address in the range, which ends where the next shard's range starts.
+    pub start: Address,
+    /// The chain that pays the airdrops of the addresses in the range.
+    pub chain_id: ChainId,
+    /// The maximum total [`Amount`] of tokens distributed by this shard.
+    pub budget: Amount,
+}
+
+/// Checks that the `shards` are sorted by the start of their address ranges, and that each shard
+/// has its own chain.
+pub(crate) fn is_valid_shard_map(shards: &[PayerShard]) -> bool {
+    let is_sorted = shards.windows(2).all(|pair| pair[0].start < pair[1].start);
+    let has_unique_chains = shards.iter().enumerate().all(|(index, shard)| {
+        shards[..index]
+            .iter()
+            .all(|previous| previous.chain_id != shard.chain_id)
+    });
+
+    is_sorted && has_unique_chains
+}
+
+/// Finds the [`PayerShard`] responsible for paying the airdrop of an `address`, if any.
+pub(crate) fn find_payer_shard<'shards>(
+    shards: &'shards [PayerShard],
+    address: &Address,
+) -> Option<&'shards PayerShard> {
+    let shard_count = shards.partition_point(|shard| shard.start <= *address);
+
+    shard_count.checked_sub(1).map(|index| &shards[index])
+}
@@ -1,23 +1,49 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use alloy_sol_types::{eip712_domain, sol, Eip712Domain};
-use linera_sdk::{abis::fungible, bcs, linera_base_types::ApplicationId};
+use alloy_primitives::{B256, U256};
+use alloy_sol_types::{eip712_domain, sol, Eip712Domain, SolStruct};
+use linera_sdk::{
+    abis::fungible,
+    bcs,
+    linera_base_types::{ApplicationId, Timestamp},
+};
 
-use crate::{ApplicationAbi, ETHEREUM_MAINNET_CHAIN_ID};
+use crate::{ApplicationAbi, EvmChain, RelayerFee};
 
-/// The EIP-712 domain for this application.
-pub const AIRDROP_CLAIM_DOMAIN: Eip712Domain = eip712_domain! {
-    name: "Linera AirDrop demo",
-    version: "0.0.1",
-    chain_id: ETHEREUM_MAINNET_CHAIN_ID,
-};
+/// Returns the EIP-712 domain for this application, for claims signed on the `chain`.
+pub fn airdrop_claim_domain(chain: EvmChain) -> Eip712Domain {
+    eip712_domain! {
+        name: "Linera AirDrop demo",
+        version: "0.0.1",
+        chain_id: chain.chain_id(),
+    }
+}
 
 sol! {
     /// EIP-712 representation of an airdrop claim.
     struct AirDropClaim {
         string appId;
         FungibleAccount claimer;
+        uint64 deadline;
+        uint64 nonce;
+    }
+
+    /// EIP-712 representation of an airdrop claim submitted by a relayer, which is paid a fee
+    /// from the airdropped tokens, counted in attos.
+    struct SponsoredAirDropClaim {
+        string appId;
+        FungibleAccount claimer;
+        uint64 deadline;
+        uint64 nonce;
+        FungibleAccount relayer;
+        uint256 relayerFee;
+    }
+
+    /// EIP-712 representation of the revocation of all airdrop claims with a lower nonce.
+    struct AirDropRevocation {
+        string appId;
+        uint64 nonce;
     }
 
     /// EIP-712 representation of a destination account.
@@ -27,19 +53,66 @@ sol! {
     }
 }
 
-impl AirDropClaim {
-    /// Creates a new [`AirDropClaim`] to be used in a signature's payload.
-    pub fn new(application_id: ApplicationId<ApplicationAbi>, claimer: &fungible::Account) -> Self {
-        let application_id_bytes =
-            bcs::to_bytes(&application_id).expect("`ApplicationId`s should be serializable");
+/// Returns the EIP-712 hash signed by a claimer for an airdrop to be delivered to the `claimer`
+/// account until the `deadline`, on the `chain`.
+///
+/// Claims with a `relayer_fee` are signed as a [`SponsoredAirDropClaim`], so that the relayer and
+/// its fee can't be changed without invalidating the signature.
+pub fn claim_signing_hash(
+    application_id: ApplicationId<ApplicationAbi>,
+    claimer: &fungible::Account,
+    deadline: Timestamp,
+    nonce: u64,
+    relayer_fee: Option<&RelayerFee>,
+    chain: EvmChain,
+) -> B256 {
+    let domain = airdrop_claim_domain(chain);
+    let app_id = encode_application_id(application_id);
+    let claimer = claimer.into();
+    let deadline = deadline.micros();
 
-        AirDropClaim {
-            appId: hex::encode(application_id_bytes),
-            claimer: claimer.into(),
+    match relayer_fee {
+        None => AirDropClaim {
+            appId: app_id,
+            claimer,
+            deadline,
+            nonce,
+        }
+        .eip712_signing_hash(&domain),
+        Some(relayer_fee) => SponsoredAirDropClaim {
+            appId: app_id,
+            claimer,
+            deadline,
+            nonce,
+            relayer: (&relayer_fee.relayer).into(),
+            relayerFee: U256::from(u128::from(relayer_fee.amount)),
         }
+        .eip712_signing_hash(&domain),
     }
 }
 
+/// Returns the EIP-712 hash signed by a claimer to revoke their claims with a nonce lower than
+/// `nonce`, on the `chain`.
+pub fn revocation_signing_hash(
+    application_id: ApplicationId<ApplicationAbi>,
+    nonce: u64,
+    chain: EvmChain,
+) -> B256 {
+    AirDropRevocation {
+        appId: encode_application_id(application_id),
+        nonce,
+    }
+    .eip712_signing_hash(&airdrop_claim_domain(chain))
+}
+
+/// Encodes the `application_id` as the hexadecimal string of its BCS representation.
+fn encode_application_id(application_id: ApplicationId<ApplicationAbi>) -> String {
+    let application_id_bytes =
+        bcs::to_bytes(&application_id).expect("`ApplicationId`s should be serializable");
+
+    hex::encode(application_id_bytes)
+}
+
 impl From<&fungible::Account> for FungibleAccount {
     fn from(account: &fungible::Account) -> Self {
         FungibleAccount {
@@ -0,0 +1,146 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sampling of balances at several blocks, so that borrowing funds for a single block isn't enough
+//! to become eligible for the airdrop.
+
+use alloy_primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::{AirDropError, RuleOracle, SnapshotAsset};
+
+/// The maximum number of blocks at which balances can be sampled, including the snapshot block.
+pub const MAX_SNAPSHOT_SAMPLES: usize = 32;
+
+/// The blocks at which the balances of claimers are sampled, and how the samples are combined.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SnapshotSampling {
+    /// The blocks to sample, in addition to the snapshot block.
+    pub blocks: SampledBlocks,
+    /// How the sampled balances are combined into the claimer's snapshot balance.
+    pub aggregation: BalanceAggregation,
+}
+
+/// The blocks at which balances are sampled.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SampledBlocks {
+    /// An explicit list of blocks.
+    List(Vec<u64>),
+    /// The blocks from `start` to `end` (inclusive), every `step` blocks.
+    Range { start: u64, end: u64, step: u64 },
+}
+
+/// How the balances sampled at several blocks are combined.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BalanceAggregation {
+    /// The lowest sampled balance.
+    Minimum,
+    /// The average of the sampled balances, where each sample is weighted by the number of blocks
+    /// until the next sample, assuming the balance doesn't change between samples.
+    ///
+    /// The last sample is weighted as a single block.
+    TimeWeightedAverage,
+}
+
+impl SnapshotSampling {
+    /// Returns the sorted and distinct blocks to sample, including the `snapshot_block`.
+    ///
+    /// Returns [`None`] if the blocks are invalid or there are more than
+    /// [`MAX_SNAPSHOT_SAMPLES`] of them.
+    pub fn blocks(&self, snapshot_block: u64) -> Option<Vec<u64>> {
+        let mut blocks = match &self.blocks {
+            SampledBlocks::List(blocks) => {
+                if blocks.len() >= MAX_SNAPSHOT_SAMPLES {
+                    return None;
+                }
+
+                blocks.clone()
+            }
+            &SampledBlocks::Range { start, end, step } => {
+                if start > end || step == 0 {
+                    return None;
+                }
+
+                let count = (end - start) / step + 1;
+
+                if count >= MAX_SNAPSHOT_SAMPLES as u64 {
+                    return None;
+                }
+
+                (0..count).map(|index| start + index * step).collect()
+            }
+        };
+
+        blocks.push(snapshot_block);
+        blocks.sort_unstable();
+        blocks.dedup();
+
+        Some(blocks)
+    }
+
+    /// Returns the balance of `asset` held by `address`, combining the balances sampled at each
+    /// block using the `oracle`.
+    ///
+    /// Blocks where the address had no balance are sampled as zero, but addresses without a
+    /// balance at any of the blocks are rejected with [`AirDropError::NotEligible`].
+    pub fn sampled_balance(
+        &self,
+        address: &Address,
+        asset: &SnapshotAsset,
+        snapshot_block: u64,
+        oracle: &mut impl RuleOracle,
+    ) -> Result<U256, AirDropError> {
+        let blocks = self
+            .blocks(snapshot_block)
+            .ok_or(AirDropError::InvalidSnapshotSampling)?;
+        let mut samples = Vec::with_capacity(blocks.len());
+
+        for &block in &blocks {
+            let balance = match oracle.balance_at(address, asset, block) {
+                Err(AirDropError::NotEligible) => None,
+                result => Some(result?),
+            };
+
+            samples.push(balance);
+        }
+
+        self.aggregate(&blocks, &samples)
+    }
+
+    /// Combines the balances sampled at each of the `blocks`, where [`None`] means the address
+    /// had no balance at the block.
+    ///
+    /// Addresses without a balance at any of the blocks are rejected with
+    /// [`AirDropError::NotEligible`].
+    pub fn aggregate(
+        &self,
+        blocks: &[u64],
+        samples: &[Option<U256>],
+    ) -> Result<U256, AirDropError> {
+        if samples.iter().all(Option::is_none) {
+            return Err(AirDropError::NotEligible);
+        }
+
+        let balances = samples.iter().map(|balance| balance.unwrap_or(U256::ZERO));
+
+        Ok(match self.aggregation {
+            BalanceAggregation::Minimum => balances.min().unwrap_or(U256::ZERO),
+            BalanceAggregation::TimeWeightedAverage => {
+                let weights = blocks
+                    .windows(2)
+                    .map(|pair| pair[1] - pair[0])
+                    .chain([1])
+                    .map(U256::from);
+                let total_weight = U256::from(blocks[blocks.len() - 1] - blocks[0] + 1);
+
+                let weighted_sum = balances
+                    .zip(weights)
+                    .fold(U256::ZERO, |sum, (balance, weight)| {
+                        sum.saturating_add(balance.saturating_mul(weight))
+                    });
+
+                weighted_sum / total_weight
+            }
+        })
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A builder for the SQL queries sent to Space-and-Time.
+//!
+//! Identifiers (schemas, tables and columns) can only be static strings defined by the
+//! application, and values are only accepted as typed [`SqlValue`]s that are rendered as escaped
+//! literals, so that no input received from users can change the structure of a query.
+
+use std::fmt::Write;
+
+use alloy_primitives::Address;
+
+/// A value compared in a [`SelectQuery`], rendered as an SQL literal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SqlValue {
+    /// An address, rendered as a string with its lowercase `0x`-prefixed hexadecimal
+    /// representation.
+    Address(Address),
+    /// An integer, rendered as a numeric literal.
+    Integer(u64),
+    /// A string, rendered with its quotes escaped.
+    Text(String),
+}
+
+/// The operator used to compare a column to a [`SqlValue`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Equal,
+    Less,
+    LessOrEqual,
+}
+
+/// A `SELECT` query on a single table, where all conditions must hold.
+#[derive(Clone, Debug)]
+pub struct SelectQuery {
+    columns: &'static str,
+    schema: &'static str,
+    table: &'static str,
+    conditions: String,
+    latest_per_group: Option<(&'static str, &'static str)>,
+    suffix: String,
+}
+
+impl SelectQuery {
+    /// Starts a query that selects the `columns` from the `table` in the `schema`.
+    pub fn new(columns: &'static str, schema: &'static str, table: &'static str) -> Self {
+        SelectQuery {
+            columns,
+            schema,
+            table,
+            conditions: String::new(),
+            latest_per_group: None,
+            suffix: String::new(),
+        }
+    }
+
+    /// Adds a condition comparing the `column` to a `value`.
+    pub fn filter(mut self, column: &'static str, comparison: Comparison, value: SqlValue) -> Self {
+        let operator = match comparison {
+            Comparison::Equal => "=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+        };
+
+        self.start_condition();
+        write!(self.conditions, "{column} {operator} ").expect("Writing to a `String` can't fail");
+        value.render(&mut self.conditions);
+        self
+    }
+
+    /// Adds a condition that the `column` is one of the `values`, which must not be empty.
+    pub fn filter_in(
+        mut self,
+        column: &'static str,
+        values: impl IntoIterator<Item = SqlValue>,
+    ) -> Self {
+        self.start_condition();
+        write!(self.conditions, "{column} IN (").expect("Writing to a `String` can't fail");
+
+        for (index, value) in values.into_iter().enumerate() {
+            if index > 0 {
+                self.conditions.push_str(", ");
+            }
+
+            value.render(&mut self.conditions);
+        }
+
+        self.conditions.push(')');
+        self
+    }
+
+    /// Only selects, among the rows that match the conditions and have the same `group_column`,
+    /// the rows with the highest `latest_column`.
+    ///
+    /// The rows are joined with a subquery that selects the highest `latest_column` of each
+    /// group, so the database only returns the latest rows.
+    pub fn latest_per_group(
+        mut self,
+        group_column: &'static str,
+        latest_column: &'static str,
+    ) -> Self {
+        self.latest_per_group = Some((group_column, latest_column));
+        self
+    }
+
+    /// Orders the rows by the `column`, in descending order.
+    pub fn order_by_descending(mut self, column: &'static str) -> Self {
+        write!(self.suffix, " ORDER BY {column} DESC").expect("Writing to a `String` can't fail");
+        self
+    }
+
+    /// Limits the query to return at most `count` rows.
+    pub fn limit(mut self, count: u64) -> Self {
+        write!(self.suffix, " LIMIT {count}").expect("Writing to a `String` can't fail");
+        self
+    }
+
+    /// Returns the SQL text of the query.
+    pub fn build(self) -> String {
+        let SelectQuery {
+            columns,
+            schema,
+            table,
+            conditions,
+            latest_per_group,
+            suffix,
+        } = self;
+
+        let mut sql = format!("SELECT {columns} FROM {schema}.{table}");
+
+        if let Some((group_column, latest_column)) = latest_per_group {
+            write!(
+                sql,
+                " JOIN (SELECT {group_column} AS LATEST_{group_column}, \
+                MAX({latest_column}) AS LATEST_{latest_column} \
+                FROM {schema}.{table}{conditions} GROUP BY {group_column}) AS LATEST \
+                ON {group_column} = LATEST_{group_column} \
+                AND {latest_column} = LATEST_{latest_column}"
+            )
+            .expect("Writing to a `String` can't fail");
+        }
+
+        sql.push_str(&conditions);
+        sql.push_str(&suffix);
+        sql.push(';');
+        sql
+    }
+
+    /// Appends the keyword that introduces the next condition.
+    fn start_condition(&mut self) {
+        if self.conditions.is_empty() {
+            self.conditions.push_str(" WHERE ");
+        } else {
+            self.conditions.push_str(" AND ");
+        }
+    }
+}
+
+impl SqlValue {
+    /// Appends this value as an SQL literal to the `sql` text.
+    fn render(&self, sql: &mut String) {
+        match self {
+            SqlValue::Address(address) => {
+                write!(sql, "'0x{}'", hex::encode(address.as_slice()))
+                    .expect("Writing to a `String` can't fail");
+            }
+            SqlValue::Integer(integer) => {
+                write!(sql, "{integer}").expect("Writing to a `String` can't fail");
+            }
+            SqlValue::Text(text) => {
+                sql.push('\'');
+                sql.push_str(&text.replace('\'', "''"));
+                sql.push('\'');
+            }
+        }
+    }
+}
@@ -2,11 +2,45 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use airdrop_demo::AirDropId;
-use linera_sdk::views::{linera_views, RootView, SetView, ViewStorageContext};
+use linera_sdk::{
+    abis::fungible::Account,
+    linera_base_types::{Amount, BlockHeight, ChainId, Timestamp},
+    views::{linera_views, MapView, RegisterView, RootView, SetView, ViewStorageContext},
+};
+use serde::{Deserialize, Serialize};
 
 /// The application state.
 #[derive(RootView, async_graphql::SimpleObject)]
 #[view(context = "ViewStorageContext")]
 pub struct Application {
-    pub handled_airdrops: SetView<AirDropId>,
+    pub handled_airdrops: MapView<AirDropId, ClaimRecord>,
+    pub distributed_total: RegisterView<Amount>,
+    pub paused: RegisterView<bool>,
+    pub claims_paid: RegisterView<u64>,
+    pub destination_chains: SetView<ChainId>,
+    pub destination_chain_count: RegisterView<u64>,
+    pub rejected_replays: RegisterView<u64>,
+    pub rejected_revoked_claims: RegisterView<u64>,
+    pub shard_funding: MapView<ChainId, Amount>,
+    /// The lowest nonce of each claimer's airdrop claims that haven't been revoked.
+    pub minimum_claim_nonces: MapView<AirDropId, u64>,
+}
+
+/// The record of an airdrop that has been paid.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, async_graphql::SimpleObject)]
+pub struct ClaimRecord {
+    pub status: ClaimStatus,
+    pub amount: Amount,
+    pub destination: Account,
+    pub block_height: BlockHeight,
+    pub timestamp: Timestamp,
+}
+
+/// How much of an airdrop has been paid.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, async_graphql::Enum)]
+pub enum ClaimStatus {
+    /// The airdrop was paid in full.
+    Paid,
+    /// The airdrop was only partially paid because the budget was exhausted.
+    PartiallyPaid,
 }
@@ -3,17 +3,17 @@
 
 //! Helper functions used in tests.
 
-use alloy_primitives::PrimitiveSignature;
-use alloy_sol_types::SolStruct;
+use alloy_primitives::{Address, PrimitiveSignature, B256, U256};
 use k256::ecdsa::SigningKey;
 use linera_sdk::{
     abis::fungible,
-    linera_base_types::{ApplicationId, CryptoHash},
+    linera_base_types::{ApplicationId, CryptoHash, Timestamp},
 };
 
 use crate::{
-    signature_payload::{self, AIRDROP_CLAIM_DOMAIN},
-    ApplicationAbi,
+    merkle_leaf, merkle_node,
+    signature_payload::{claim_signing_hash, revocation_signing_hash},
+    ApplicationAbi, EvmChain, MerkleProof, RelayerFee,
 };
 
 /// Creates a dummy [`ApplicationId`] to use as the Fungible Token for testing.
@@ -26,18 +26,121 @@ pub fn create_dummy_application_id<Abi>(name: &str) -> ApplicationId<Abi> {
     ApplicationId::new(CryptoHash::test_hash(name)).with_abi()
 }
 
-/// Creates a [`PrimitiveSignature`] for an airdrop claim.
+/// Creates a [`PrimitiveSignature`] for an airdrop claim signed on Ethereum, valid until the
+/// `deadline`.
 pub fn sign_claim(
     signer: &SigningKey,
     application_id: ApplicationId<ApplicationAbi>,
     claimer: fungible::Account,
+    deadline: Timestamp,
+    nonce: u64,
 ) -> PrimitiveSignature {
-    let payload = signature_payload::AirDropClaim::new(application_id, &claimer);
+    sign_claim_for_chain(
+        signer,
+        EvmChain::Ethereum,
+        application_id,
+        claimer,
+        deadline,
+        nonce,
+    )
+}
+
+/// Creates a [`PrimitiveSignature`] for an airdrop claim signed on the `chain`, valid until the
+/// `deadline`.
+pub fn sign_claim_for_chain(
+    signer: &SigningKey,
+    chain: EvmChain,
+    application_id: ApplicationId<ApplicationAbi>,
+    claimer: fungible::Account,
+    deadline: Timestamp,
+    nonce: u64,
+) -> PrimitiveSignature {
+    let hash = claim_signing_hash(application_id, &claimer, deadline, nonce, None, chain);
+
+    sign_hash(signer, hash)
+}
+
+/// Creates a [`PrimitiveSignature`] for an airdrop claim signed on Ethereum, valid until the
+/// `deadline`, to be submitted by a relayer that is paid the `relayer_fee`.
+pub fn sign_sponsored_claim(
+    signer: &SigningKey,
+    application_id: ApplicationId<ApplicationAbi>,
+    claimer: fungible::Account,
+    deadline: Timestamp,
+    nonce: u64,
+    relayer_fee: &RelayerFee,
+) -> PrimitiveSignature {
+    let hash = claim_signing_hash(
+        application_id,
+        &claimer,
+        deadline,
+        nonce,
+        Some(relayer_fee),
+        EvmChain::Ethereum,
+    );
 
-    let hash = payload.eip712_signing_hash(&AIRDROP_CLAIM_DOMAIN);
+    sign_hash(signer, hash)
+}
+
+/// Creates a [`PrimitiveSignature`] signed on Ethereum to revoke the airdrop claims with a nonce
+/// lower than `nonce`.
+pub fn sign_revocation(
+    signer: &SigningKey,
+    application_id: ApplicationId<ApplicationAbi>,
+    nonce: u64,
+) -> PrimitiveSignature {
+    let hash = revocation_signing_hash(application_id, nonce, EvmChain::Ethereum);
+
+    sign_hash(signer, hash)
+}
 
+/// Signs a payload `hash` with the `signer`'s key.
+fn sign_hash(signer: &SigningKey, hash: B256) -> PrimitiveSignature {
     signer
         .sign_prehash_recoverable(hash.as_slice())
         .expect("Payload hash should be signable with `SigningKey`")
         .into()
 }
+
+/// Builds a Merkle tree for a snapshot with the provided `(address, balance)` entries.
+///
+/// Returns the Merkle root and a [`MerkleProof`] for each entry, in the same order as the entries.
+pub fn create_merkle_snapshot(entries: &[(Address, U256)]) -> (B256, Vec<MerkleProof>) {
+    let mut proofs = entries
+        .iter()
+        .map(|&(_, balance)| MerkleProof {
+            balance,
+            siblings: vec![],
+        })
+        .collect::<Vec<_>>();
+    let mut positions = (0..entries.len()).collect::<Vec<_>>();
+    let mut layer = entries
+        .iter()
+        .map(|(address, balance)| merkle_leaf(address, *balance))
+        .collect::<Vec<_>>();
+
+    assert!(
+        !layer.is_empty(),
+        "Merkle snapshot needs at least one entry"
+    );
+
+    while layer.len() > 1 {
+        for (proof, position) in proofs.iter_mut().zip(&mut positions) {
+            if let Some(sibling) = layer.get(*position ^ 1) {
+                proof.siblings.push(*sibling);
+            }
+            *position /= 2;
+        }
+
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_node(*left, *right),
+                [single] => *single,
+                _ => unreachable!("`chunks(2)` yields one or two nodes"),
+            })
+            .collect();
+    }
+
+    (layer[0], proofs)
+}
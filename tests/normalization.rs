@@ -0,0 +1,233 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the normalization of Space-and-Time query results, using responses recorded in
+//! `tests/fixtures/space_and_time`.
+
+use airdrop_demo::{
+    normalize_snapshot_balance, normalize_wallet_balances, AirDropError, SnapshotBalance,
+    WalletBalance,
+};
+use alloy_primitives::{Address, U256};
+
+/// The balance in the recorded responses, in wei.
+const RECORDED_BALANCE: u64 = 1_500_000_000_000_000_000;
+
+/// The block number in the recorded responses.
+const RECORDED_BLOCK_NUMBER: u64 = 19_000_000;
+
+/// Loads a recorded response from the fixtures directory.
+macro_rules! fixture {
+    ($name:literal) => {
+        include_bytes!(concat!("fixtures/space_and_time/", $name, ".json")).as_slice()
+    };
+}
+
+/// Tests if a balance encoded as a decimal string is normalized.
+#[test]
+fn normalizes_decimal_string_balance() {
+    assert_eq!(
+        normalize_snapshot_balance(fixture!("balance_string"), "BALANCE")
+            .expect("Failed to normalize recorded response"),
+        Some(SnapshotBalance {
+            balance: U256::from(RECORDED_BALANCE),
+            block_number: None,
+        })
+    );
+}
+
+/// Tests if the balance column is found regardless of its casing in the response or in the query.
+#[test]
+fn normalizes_column_casing() {
+    let expected = Some(SnapshotBalance {
+        balance: U256::from(RECORDED_BALANCE),
+        block_number: None,
+    });
+
+    for response in [
+        fixture!("balance_string"),
+        fixture!("balance_lowercase_column"),
+        fixture!("balance_mixed_case_column"),
+    ] {
+        for column in ["BALANCE", "balance", "Balance"] {
+            assert_eq!(
+                normalize_snapshot_balance(response, column)
+                    .expect("Failed to normalize recorded response"),
+                expected
+            );
+        }
+    }
+}
+
+/// Tests if balances encoded as JSON numbers, hexadecimal strings and decimal strings with a zero
+/// fractional part are normalized to the same value.
+#[test]
+fn normalizes_numeric_encodings() {
+    for response in [
+        fixture!("balance_number"),
+        fixture!("balance_hex_string"),
+        fixture!("balance_decimal_fraction"),
+    ] {
+        assert_eq!(
+            normalize_snapshot_balance(response, "BALANCE")
+                .expect("Failed to normalize recorded response"),
+            Some(SnapshotBalance {
+                balance: U256::from(RECORDED_BALANCE),
+                block_number: None,
+            })
+        );
+    }
+}
+
+/// Tests if a JSON floating point number with an exact integer value is normalized.
+#[test]
+fn normalizes_exact_float_balance() {
+    assert_eq!(
+        normalize_snapshot_balance(fixture!("balance_float_number"), "BALANCE")
+            .expect("Failed to normalize recorded response"),
+        Some(SnapshotBalance {
+            balance: U256::from(4_200),
+            block_number: None,
+        })
+    );
+}
+
+/// Tests if the largest possible balance is normalized without losing precision.
+#[test]
+fn normalizes_maximum_balance() {
+    assert_eq!(
+        normalize_snapshot_balance(fixture!("balance_max_u256"), "BALANCE")
+            .expect("Failed to normalize recorded response"),
+        Some(SnapshotBalance {
+            balance: U256::MAX,
+            block_number: None,
+        })
+    );
+}
+
+/// Tests if the block number is normalized when the query selects it, in any casing and
+/// encoding.
+#[test]
+fn normalizes_block_number() {
+    for response in [
+        fixture!("balance_with_block_number"),
+        fixture!("balance_with_hex_block_number"),
+    ] {
+        assert_eq!(
+            normalize_snapshot_balance(response, "BALANCE")
+                .expect("Failed to normalize recorded response"),
+            Some(SnapshotBalance {
+                balance: U256::from(RECORDED_BALANCE),
+                block_number: Some(RECORDED_BLOCK_NUMBER),
+            })
+        );
+    }
+}
+
+/// Tests if a response without rows is normalized to no balance.
+#[test]
+fn normalizes_empty_result() {
+    assert_eq!(
+        normalize_snapshot_balance(fixture!("no_rows"), "BALANCE")
+            .expect("Failed to normalize recorded response"),
+        None
+    );
+}
+
+/// Tests if responses whose rows can't be interpreted unambiguously are rejected.
+#[test]
+fn rejects_ambiguous_rows() {
+    for response in [
+        fixture!("multiple_rows"),
+        fixture!("duplicate_column"),
+        fixture!("unexpected_column"),
+        fixture!("missing_column"),
+    ] {
+        assert!(matches!(
+            normalize_snapshot_balance(response, "BALANCE"),
+            Err(AirDropError::MalformedOracleResponse(_))
+        ));
+    }
+}
+
+/// Tests if balances that aren't non-negative integers are rejected.
+#[test]
+fn rejects_invalid_balances() {
+    for response in [
+        fixture!("negative_balance"),
+        fixture!("negative_balance_string"),
+        fixture!("fractional_balance"),
+        fixture!("imprecise_float_balance"),
+        fixture!("null_balance"),
+        fixture!("empty_hex_balance"),
+        fixture!("overflowing_balance"),
+        fixture!("overflowing_block_number"),
+    ] {
+        assert!(matches!(
+            normalize_snapshot_balance(response, "BALANCE"),
+            Err(AirDropError::MalformedOracleResponse(_))
+        ));
+    }
+}
+
+/// Tests if an error object returned instead of the query result is rejected.
+#[test]
+fn rejects_error_response() {
+    assert!(matches!(
+        normalize_snapshot_balance(fixture!("error_object"), "BALANCE"),
+        Err(AirDropError::MalformedOracleResponse(_))
+    ));
+}
+
+/// Tests if the rows of a query with several wallets are normalized in order, with their wallet
+/// addresses in any casing.
+#[test]
+fn normalizes_wallet_balances() {
+    let first_wallet: Address = "0x52908400098527886E0F7030069857D2E4169EE7"
+        .parse()
+        .expect("Invalid test address");
+    let second_wallet: Address = "0x8617E340B3D01FA5F11F306F4090FD50E238070D"
+        .parse()
+        .expect("Invalid test address");
+
+    assert_eq!(
+        normalize_wallet_balances(fixture!("wallet_balances"), "BALANCE")
+            .expect("Failed to normalize recorded response"),
+        vec![
+            WalletBalance {
+                wallet_address: first_wallet,
+                snapshot: SnapshotBalance {
+                    balance: U256::from(RECORDED_BALANCE),
+                    block_number: None,
+                },
+            },
+            WalletBalance {
+                wallet_address: second_wallet,
+                snapshot: SnapshotBalance {
+                    balance: U256::from(25),
+                    block_number: None,
+                },
+            },
+        ]
+    );
+}
+
+/// Tests if rows of a query with several wallets are rejected if they don't have a valid wallet
+/// address, and if a wallet address is returned by a query for a single wallet.
+#[test]
+fn rejects_invalid_wallet_rows() {
+    for response in [
+        fixture!("wallet_balances_invalid_address"),
+        fixture!("wallet_balances_missing_address"),
+    ] {
+        assert!(matches!(
+            normalize_wallet_balances(response, "BALANCE"),
+            Err(AirDropError::MalformedOracleResponse(_))
+        ));
+    }
+
+    assert!(matches!(
+        normalize_snapshot_balance(fixture!("unexpected_column"), "BALANCE"),
+        Err(AirDropError::MalformedOracleResponse(_))
+    ));
+}
@@ -0,0 +1,141 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the builder of the SQL queries sent to Space-and-Time.
+
+use airdrop_demo::{Comparison, SelectQuery, SqlValue};
+use alloy_primitives::Address;
+
+/// Tests if a query is rendered with its conditions, ordering and limit.
+#[test]
+fn builds_select_query() {
+    let address = Address::repeat_byte(0xab);
+
+    let query = SelectQuery::new("BALANCE", "ETHEREUM", "NATIVE_WALLETS")
+        .filter(
+            "WALLET_ADDRESS",
+            Comparison::Equal,
+            SqlValue::Address(address),
+        )
+        .filter(
+            "BLOCK_NUMBER",
+            Comparison::LessOrEqual,
+            SqlValue::Integer(100),
+        )
+        .order_by_descending("BLOCK_NUMBER")
+        .limit(1)
+        .build();
+
+    assert_eq!(
+        query,
+        "SELECT BALANCE FROM ETHEREUM.NATIVE_WALLETS \
+        WHERE WALLET_ADDRESS = '0xabababababababababababababababababababab' \
+        AND BLOCK_NUMBER <= 100 \
+        ORDER BY BLOCK_NUMBER DESC \
+        LIMIT 1;"
+    );
+}
+
+/// Tests if addresses are always rendered in canonical lowercase hexadecimal, regardless of how
+/// they were written by the user.
+#[test]
+fn renders_canonical_addresses() {
+    let checksummed: Address = "0x52908400098527886E0F7030069857D2E4169EE7"
+        .parse()
+        .expect("Invalid test address");
+
+    let query = SelectQuery::new("BALANCE", "ETHEREUM", "NATIVE_WALLETS")
+        .filter(
+            "WALLET_ADDRESS",
+            Comparison::Equal,
+            SqlValue::Address(checksummed),
+        )
+        .build();
+
+    assert_eq!(
+        query,
+        "SELECT BALANCE FROM ETHEREUM.NATIVE_WALLETS \
+        WHERE WALLET_ADDRESS = '0x52908400098527886e0f7030069857d2e4169ee7';"
+    );
+}
+
+/// Tests if quotes in text values are escaped, so that they can't terminate the literal.
+#[test]
+fn escapes_text_values() {
+    let query = SelectQuery::new("BALANCE", "ETHEREUM", "ERC1155_WALLETS")
+        .filter(
+            "TOKEN_ID",
+            Comparison::Equal,
+            SqlValue::Text("1' OR '1' = '1".to_owned()),
+        )
+        .filter("BLOCK_NUMBER", Comparison::Less, SqlValue::Integer(5))
+        .build();
+
+    assert_eq!(
+        query,
+        "SELECT BALANCE FROM ETHEREUM.ERC1155_WALLETS \
+        WHERE TOKEN_ID = '1'' OR ''1'' = ''1' \
+        AND BLOCK_NUMBER < 5;"
+    );
+}
+
+/// Tests if a condition on a list of values renders each value as an escaped literal.
+#[test]
+fn builds_filter_with_value_list() {
+    let query = SelectQuery::new("WALLET_ADDRESS, BALANCE", "ETHEREUM", "NATIVE_WALLETS")
+        .filter_in(
+            "WALLET_ADDRESS",
+            [Address::repeat_byte(0x01), Address::repeat_byte(0x02)].map(SqlValue::Address),
+        )
+        .filter(
+            "BLOCK_NUMBER",
+            Comparison::LessOrEqual,
+            SqlValue::Integer(7),
+        )
+        .order_by_descending("BLOCK_NUMBER")
+        .build();
+
+    assert_eq!(
+        query,
+        "SELECT WALLET_ADDRESS, BALANCE FROM ETHEREUM.NATIVE_WALLETS \
+        WHERE WALLET_ADDRESS IN (\
+        '0x0101010101010101010101010101010101010101', \
+        '0x0202020202020202020202020202020202020202') \
+        AND BLOCK_NUMBER <= 7 \
+        ORDER BY BLOCK_NUMBER DESC;"
+    );
+}
+
+/// Tests if a query can select only the latest row of each group, by joining the rows with their
+/// group's latest value.
+#[test]
+fn builds_latest_row_per_group_query() {
+    let query = SelectQuery::new("WALLET_ADDRESS, BALANCE", "ETHEREUM", "ERC20_WALLETS")
+        .filter(
+            "TOKEN_ADDRESS",
+            Comparison::Equal,
+            SqlValue::Address(Address::repeat_byte(0x01)),
+        )
+        .filter(
+            "BLOCK_NUMBER",
+            Comparison::LessOrEqual,
+            SqlValue::Integer(7),
+        )
+        .latest_per_group("WALLET_ADDRESS", "BLOCK_NUMBER")
+        .build();
+
+    assert_eq!(
+        query,
+        "SELECT WALLET_ADDRESS, BALANCE FROM ETHEREUM.ERC20_WALLETS \
+        JOIN (SELECT WALLET_ADDRESS AS LATEST_WALLET_ADDRESS, \
+        MAX(BLOCK_NUMBER) AS LATEST_BLOCK_NUMBER \
+        FROM ETHEREUM.ERC20_WALLETS \
+        WHERE TOKEN_ADDRESS = '0x0101010101010101010101010101010101010101' \
+        AND BLOCK_NUMBER <= 7 \
+        GROUP BY WALLET_ADDRESS) AS LATEST \
+        ON WALLET_ADDRESS = LATEST_WALLET_ADDRESS \
+        AND BLOCK_NUMBER = LATEST_BLOCK_NUMBER \
+        WHERE TOKEN_ADDRESS = '0x0101010101010101010101010101010101010101' \
+        AND BLOCK_NUMBER <= 7;"
+    );
+}
@@ -1,17 +1,36 @@
 import React, { useState } from 'react';
-import { gql, useMutation } from '@apollo/client';
+import { gql, useMutation, useQuery } from '@apollo/client';
 import web3, { Web3 } from 'web3';
-import { AirDropClaimMutation } from './qql/graphql';
+import { AirDropClaimMutation, AirDropClaimMutationVariables, SourceChainIdQuery } from './qql/graphql';
 import logo from './logo.svg';
 import './App.css';
 
 const CLAIM_AIRDROP = gql`
-    mutation AirDropClaim($destination: FungibleAccount!, $signature: String!, $apiToken: String!) {
-        airDropClaim(destination: $destination, signature: $signature, apiToken: $apiToken)
+    mutation AirDropClaim(
+        $destination: FungibleAccount!,
+        $deadline: Timestamp!,
+        $nonce: Int!,
+        $signature: String!,
+        $apiToken: String!,
+    ) {
+        airDropClaim(
+            destination: $destination,
+            deadline: $deadline,
+            nonce: $nonce,
+            signature: $signature,
+            apiToken: $apiToken,
+        )
     }
 `;
 
-const ETHEREUM_MAINNET_CHAIN_ID = 1;
+const SOURCE_CHAIN_ID = gql`
+    query SourceChainId {
+        sourceChainId
+    }
+`;
+
+// How long a signed claim remains valid, in milliseconds.
+const CLAIM_SIGNATURE_VALIDITY_MS = 60 * 60 * 1000;
 
 type AppProps = {
   appId: string,
@@ -23,7 +42,8 @@ type AppProps = {
 
 function App({ appId, chainId, owner, userAccount, web3Provider }: AppProps) {
   const [apiToken, setApiToken] = useState("")
-  const [claim] = useMutation<AirDropClaimMutation>(CLAIM_AIRDROP, {
+  const { data: sourceChain } = useQuery<SourceChainIdQuery>(SOURCE_CHAIN_ID);
+  const [claim] = useMutation<AirDropClaimMutation, AirDropClaimMutationVariables>(CLAIM_AIRDROP, {
     onError: (error) => console.log(error),
     onCompleted: () => {},
   });
@@ -48,14 +68,23 @@ function App({ appId, chainId, owner, userAccount, web3Provider }: AppProps) {
     if (web3Provider == null) {
       throw Error('Missing Web3 provider. The Claim button should have been disabled');
     }
+    if (sourceChain == null) {
+      throw Error('Missing source chain ID. The Claim button should have been disabled');
+    }
 
     const web3 = new Web3(web3Provider.provider);
 
+    // Signatures are valid for an hour. Using the current time as the nonce makes every new
+    // signature's nonce larger than the previous ones, so revoking a nonce revokes all older claims.
+    const now = Date.now();
+    const deadline = (now + CLAIM_SIGNATURE_VALIDITY_MS) * 1000;
+    const nonce = now;
+
     web3.eth.signTypedData(userAccount, {
       domain: {
         name: "Linera AirDrop demo",
         version: "0.0.1",
-        chainId: ETHEREUM_MAINNET_CHAIN_ID,
+        chainId: sourceChain.sourceChainId,
       },
       primaryType: "AirDropClaim",
       types: {
@@ -67,6 +96,8 @@ function App({ appId, chainId, owner, userAccount, web3Provider }: AppProps) {
         AirDropClaim: [
           { name: "appId", type: "string" },
           { name: "claimer", type: "FungibleAccount" },
+          { name: "deadline", type: "uint64" },
+          { name: "nonce", type: "uint64" },
         ],
         FungibleAccount: [
           { name: "chainId", type: "string" },
@@ -76,12 +107,16 @@ function App({ appId, chainId, owner, userAccount, web3Provider }: AppProps) {
       message: {
         appId,
         claimer,
+        deadline,
+        nonce,
       },
     }).then((signature) => {
         claim({
           variables: {
             signature,
             destination: claimer,
+            deadline,
+            nonce,
             apiToken,
           },
         }).then((result) => console.log("Claimed " + result));
@@ -100,7 +135,7 @@ function App({ appId, chainId, owner, userAccount, web3Provider }: AppProps) {
             value={apiToken}
             onChange={handleApiTokenChange}
           />
-          <button type="submit" disabled={userAccount == null || web3Provider == null}>
+          <button type="submit" disabled={userAccount == null || web3Provider == null || sourceChain == null}>
             Claim
           </button>
         </form>
@@ -13,7 +13,8 @@ import { TypedDocumentNode as DocumentNode } from '@graphql-typed-document-node/
  * Therefore it is highly recommended to use the babel or swc plugin for production.
  */
 const documents = {
-    "\n    mutation AirDropClaim($destination: FungibleAccount!, $signature: String!, $apiToken: String!) {\n        airDropClaim(destination: $destination, signature: $signature, apiToken: $apiToken)\n    }\n": types.AirDropClaimDocument,
+    "\n    mutation AirDropClaim(\n        $destination: FungibleAccount!,\n        $deadline: Timestamp!,\n        $nonce: Int!,\n        $signature: String!,\n        $apiToken: String!,\n    ) {\n        airDropClaim(\n            destination: $destination,\n            deadline: $deadline,\n            nonce: $nonce,\n            signature: $signature,\n            apiToken: $apiToken,\n        )\n    }\n": types.AirDropClaimDocument,
+    "\n    query SourceChainId {\n        sourceChainId\n    }\n": types.SourceChainIdDocument,
 };
 
 /**
@@ -33,7 +34,11 @@ export function graphql(source: string): unknown;
 /**
  * The graphql function is used to parse GraphQL queries into a document that can be used by GraphQL clients.
  */
-export function graphql(source: "\n    mutation AirDropClaim($destination: FungibleAccount!, $signature: String!, $apiToken: String!) {\n        airDropClaim(destination: $destination, signature: $signature, apiToken: $apiToken)\n    }\n"): (typeof documents)["\n    mutation AirDropClaim($destination: FungibleAccount!, $signature: String!, $apiToken: String!) {\n        airDropClaim(destination: $destination, signature: $signature, apiToken: $apiToken)\n    }\n"];
+export function graphql(source: "\n    mutation AirDropClaim(\n        $destination: FungibleAccount!,\n        $deadline: Timestamp!,\n        $nonce: Int!,\n        $signature: String!,\n        $apiToken: String!,\n    ) {\n        airDropClaim(\n            destination: $destination,\n            deadline: $deadline,\n            nonce: $nonce,\n            signature: $signature,\n            apiToken: $apiToken,\n        )\n    }\n"): (typeof documents)["\n    mutation AirDropClaim(\n        $destination: FungibleAccount!,\n        $deadline: Timestamp!,\n        $nonce: Int!,\n        $signature: String!,\n        $apiToken: String!,\n    ) {\n        airDropClaim(\n            destination: $destination,\n            deadline: $deadline,\n            nonce: $nonce,\n            signature: $signature,\n            apiToken: $apiToken,\n        )\n    }\n"];
+/**
+ * The graphql function is used to parse GraphQL queries into a document that can be used by GraphQL clients.
+ */
+export function graphql(source: "\n    query SourceChainId {\n        sourceChainId\n    }\n"): (typeof documents)["\n    query SourceChainId {\n        sourceChainId\n    }\n"];
 
 export function graphql(source: string) {
   return (documents as any)[source] ?? {};
@@ -14,15 +14,65 @@ export type Scalars = {
   Boolean: { input: boolean; output: boolean; }
   Int: { input: number; output: number; }
   Float: { input: number; output: number; }
-  /** An owner of an account. */
+  /** A unique identifier for a user or an application. */
   AccountOwner: { input: any; output: any; }
+  AirDropClaim: { input: any; output: any; }
+  AirDropId: { input: any; output: any; }
+  /** A non-negative amount of tokens. */
+  Amount: { input: any; output: any; }
+  /** A block height to identify blocks in a chain */
+  BlockHeight: { input: any; output: any; }
   /** The unique identifier (UID) of a chain. This is currently computed as the hash value of a ChainDescription. */
   ChainId: { input: any; output: any; }
+  MerkleProof: { input: any; output: any; }
+  /** A timestamp, in microseconds since the Unix epoch */
+  Timestamp: { input: any; output: any; }
 };
 
-/** Empty additional fields */
-export type EmptyFields = {
-  __typename?: 'EmptyFields';
+/** An account. */
+export type Account = {
+  __typename?: 'Account';
+  /** Chain ID of the account */
+  chainId: Scalars['ChainId']['output'];
+  /** Owner of the account */
+  owner: Scalars['AccountOwner']['output'];
+};
+
+/** The record of an airdrop that has been paid. */
+export type ClaimRecord = {
+  __typename?: 'ClaimRecord';
+  amount: Scalars['Amount']['output'];
+  blockHeight: Scalars['BlockHeight']['output'];
+  destination: Account;
+  status: ClaimStatus;
+  timestamp: Scalars['Timestamp']['output'];
+};
+
+/** A [`ClaimRecord`] in the history of paid airdrops. */
+export type ClaimRecordEntry = {
+  __typename?: 'ClaimRecordEntry';
+  /** The claimer's address, with its EIP-55 checksum. */
+  address: Scalars['String']['output'];
+  record: ClaimRecord;
+};
+
+/** How much of an airdrop has been paid. */
+export enum ClaimStatus {
+  /** The airdrop was paid in full. */
+  Paid = 'PAID',
+  /** The airdrop was only partially paid because the budget was exhausted. */
+  PartiallyPaid = 'PARTIALLY_PAID'
+}
+
+/** The result of checking if an address is eligible to claim an airdrop. */
+export type EligibilityEntry = {
+  __typename?: 'EligibilityEntry';
+  /** The checked address, with its EIP-55 checksum. */
+  address: Scalars['String']['output'];
+  /** The address's snapshot balance, or `null` if it isn't part of the snapshot. */
+  balance?: Maybe<Scalars['String']['output']>;
+  /** If the address is eligible to claim an airdrop. */
+  eligible: Scalars['Boolean']['output'];
 };
 
 /** An account. */
@@ -35,19 +85,193 @@ export type FungibleAccount = {
 
 export type Mutation = {
   __typename?: 'Mutation';
-  /** Claims an airdrop. */
+  /**
+   * Claims an airdrop.
+   *
+   * Claims submitted by a relayer may include a `relayerFee` signed by the claimer, which is
+   * deducted from the airdrop and paid to the relayer.
+   */
   airDropClaim: Array<Scalars['Int']['output']>;
+  /** Claims the airdrops of several claimers at once. */
+  airDropClaimBatch: Array<Scalars['Int']['output']>;
+  /** Funds the shards with their budgets. */
+  fundShards: Array<Scalars['Int']['output']>;
+  /** Pauses the airdrop. */
+  pause: Array<Scalars['Int']['output']>;
+  /** Requests a `shard` to return its surplus tokens to the creator chain. */
+  rebalance: Array<Scalars['Int']['output']>;
+  /** Resumes the airdrop. */
+  resume: Array<Scalars['Int']['output']>;
+  /** Revokes the signed airdrop claims with a nonce lower than `nonce`. */
+  revokeClaims: Array<Scalars['Int']['output']>;
+  /** Sweeps the tokens that were not claimed to a `target` account. */
+  sweep: Array<Scalars['Int']['output']>;
 };
 
 
 export type MutationAirDropClaimArgs = {
   apiToken: Scalars['String']['input'];
+  deadline: Scalars['Timestamp']['input'];
   destination: FungibleAccount;
+  merkleProof?: InputMaybe<Scalars['MerkleProof']['input']>;
+  nonce: Scalars['Int']['input'];
+  relayerFee?: InputMaybe<RelayerFee>;
+  signature: Scalars['String']['input'];
+};
+
+
+export type MutationAirDropClaimBatchArgs = {
+  claims: Array<Scalars['AirDropClaim']['input']>;
+};
+
+
+export type MutationRebalanceArgs = {
+  shard: Scalars['ChainId']['input'];
+};
+
+
+export type MutationRevokeClaimsArgs = {
+  nonce: Scalars['Int']['input'];
   signature: Scalars['String']['input'];
 };
 
+
+export type MutationSweepArgs = {
+  target: FungibleAccount;
+};
+
+export type Query = {
+  __typename?: 'Query';
+  /**
+   * Checks if an address is eligible to claim an airdrop.
+   *
+   * Returns the address's balance at the snapshot block if it is eligible, or `null` otherwise.
+   * If the airdrop samples balances at several blocks, the returned balance combines the
+   * samples.
+   */
+  checkEligibility?: Maybe<Scalars['String']['output']>;
+  /**
+   * Checks if each of the `addresses` is eligible to claim an airdrop.
+   *
+   * Space-and-Time is queried for many addresses at once, in chunks of at most
+   * [`MAX_ADDRESSES_PER_QUERY`] addresses. At most [`MAX_ELIGIBILITY_BATCH_SIZE`] addresses can
+   * be checked in a single query.
+   */
+  checkEligibilityBatch: Array<EligibilityEntry>;
+  /**
+   * Evaluates the eligibility rule of the airdrop for an address.
+   *
+   * Returns `null` if the airdrop has no eligibility rule.
+   */
+  checkRule?: Maybe<RuleEvaluation>;
+  /**
+   * Evaluates the eligibility rule of the airdrop for each of the `addresses`, returning the
+   * evaluations in the same order.
+   *
+   * Returns `null` if the airdrop has no eligibility rule. The criteria are still queried
+   * separately for each address, so this only saves the contract from querying the service
+   * once per claim. At most [`MAX_CLAIM_BATCH_SIZE`] addresses can be checked at once.
+   */
+  checkRuleBatch?: Maybe<Array<RuleEvaluation>>;
+  /** Returns the [`Timestamp`] from which claims are no longer accepted. */
+  claimEnd: Scalars['Timestamp']['output'];
+  /** Returns the [`ClaimRecord`] of the airdrop paid for an `address`, if it has been paid. */
+  claimRecord?: Maybe<ClaimRecord>;
+  /**
+   * Returns a page of the history of paid airdrops.
+   *
+   * Skips the first `offset` records and returns at most `limit` records, up to
+   * [`MAX_CLAIM_RECORDS_PAGE_SIZE`].
+   */
+  claimRecords: Array<ClaimRecordEntry>;
+  /** Returns the earliest [`Timestamp`] at which claims are accepted. */
+  claimStart: Scalars['Timestamp']['output'];
+  /** Returns whether the airdrop is paused. */
+  paused: Scalars['Boolean']['output'];
+  /** Returns the [`Amount`] of tokens that can still be distributed by this chain. */
+  remainingBudget: Scalars['Amount']['output'];
+  /**
+   * Returns the [EIP-155] chain ID of the EVM chain where the snapshot is taken, which is part
+   * of the EIP-712 domain of the claims' signatures.
+   *
+   * [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+   */
+  sourceChainId: Scalars['Int']['output'];
+  /** Returns the aggregate [`Statistics`] of the airdrop campaign. */
+  stats: Statistics;
+};
+
+
+export type QueryCheckEligibilityArgs = {
+  address: Scalars['String']['input'];
+  apiToken: Scalars['String']['input'];
+};
+
+
+export type QueryCheckEligibilityBatchArgs = {
+  addresses: Array<Scalars['String']['input']>;
+  apiToken: Scalars['String']['input'];
+};
+
+
+export type QueryCheckRuleArgs = {
+  address: Scalars['String']['input'];
+  apiToken: Scalars['String']['input'];
+};
+
+
+export type QueryCheckRuleBatchArgs = {
+  addresses: Array<Scalars['String']['input']>;
+  apiToken: Scalars['String']['input'];
+};
+
+
+export type QueryClaimRecordArgs = {
+  address: Scalars['AirDropId']['input'];
+};
+
+
+export type QueryClaimRecordsArgs = {
+  limit?: Scalars['Int']['input'];
+  offset?: Scalars['Int']['input'];
+};
+
+/** A fee deducted from an airdrop and paid to the relayer that submitted its claim. */
+export type RelayerFee = {
+  /** The [`Amount`] of airdropped tokens paid to the relayer. */
+  amount: Scalars['Amount']['input'];
+  /** The account that receives the fee. */
+  relayer: FungibleAccount;
+};
+
+/** The result of evaluating an [`EligibilityRule`] for a claimer. */
+export type RuleEvaluation = {
+  __typename?: 'RuleEvaluation';
+  /** A description of the criteria that were checked and their results. */
+  explanation: Scalars['String']['output'];
+  /** If the claimer satisfies the rule. */
+  satisfied: Scalars['Boolean']['output'];
+};
+
+/** Aggregate statistics of the airdrop campaign. */
+export type Statistics = {
+  __typename?: 'Statistics';
+  /** The number of airdrops that have been paid. */
+  claimsPaid: Scalars['Int']['output'];
+  /** The number of distinct chains that tokens have been paid to. */
+  destinationChains: Scalars['Int']['output'];
+  /** The number of approved airdrops that were ignored because they had already been paid. */
+  rejectedReplays: Scalars['Int']['output'];
+  /** The number of approved airdrops that were ignored because their claims had been revoked. */
+  rejectedRevokedClaims: Scalars['Int']['output'];
+  /** The total [`Amount`] of tokens that have been paid. */
+  totalDistributed: Scalars['Amount']['output'];
+};
+
 export type AirDropClaimMutationVariables = Exact<{
   destination: FungibleAccount;
+  deadline: Scalars['Timestamp']['input'];
+  nonce: Scalars['Int']['input'];
   signature: Scalars['String']['input'];
   apiToken: Scalars['String']['input'];
 }>;
@@ -55,5 +279,11 @@ export type AirDropClaimMutationVariables = Exact<{
 
 export type AirDropClaimMutation = { __typename?: 'Mutation', airDropClaim: Array<number> };
 
+export type SourceChainIdQueryVariables = Exact<{ [key: string]: never; }>;
+
+
+export type SourceChainIdQuery = { __typename?: 'Query', sourceChainId: number };
+
 
-export const AirDropClaimDocument = {"kind":"Document","definitions":[{"kind":"OperationDefinition","operation":"mutation","name":{"kind":"Name","value":"AirDropClaim"},"variableDefinitions":[{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"destination"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"FungibleAccount"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"signature"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"String"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"apiToken"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"String"}}}}],"selectionSet":{"kind":"SelectionSet","selections":[{"kind":"Field","name":{"kind":"Name","value":"airDropClaim"},"arguments":[{"kind":"Argument","name":{"kind":"Name","value":"destination"},"value":{"kind":"Variable","name":{"kind":"Name","value":"destination"}}},{"kind":"Argument","name":{"kind":"Name","value":"signature"},"value":{"kind":"Variable","name":{"kind":"Name","value":"signature"}}},{"kind":"Argument","name":{"kind":"Name","value":"apiToken"},"value":{"kind":"Variable","name":{"kind":"Name","value":"apiToken"}}}]}]}}]} as unknown as DocumentNode<AirDropClaimMutation, AirDropClaimMutationVariables>;
\ No newline at end of file
+export const AirDropClaimDocument = {"kind":"Document","definitions":[{"kind":"OperationDefinition","operation":"mutation","name":{"kind":"Name","value":"AirDropClaim"},"variableDefinitions":[{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"destination"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"FungibleAccount"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"deadline"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"Timestamp"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"nonce"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"Int"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"signature"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"String"}}}},{"kind":"VariableDefinition","variable":{"kind":"Variable","name":{"kind":"Name","value":"apiToken"}},"type":{"kind":"NonNullType","type":{"kind":"NamedType","name":{"kind":"Name","value":"String"}}}}],"selectionSet":{"kind":"SelectionSet","selections":[{"kind":"Field","name":{"kind":"Name","value":"airDropClaim"},"arguments":[{"kind":"Argument","name":{"kind":"Name","value":"destination"},"value":{"kind":"Variable","name":{"kind":"Name","value":"destination"}}},{"kind":"Argument","name":{"kind":"Name","value":"deadline"},"value":{"kind":"Variable","name":{"kind":"Name","value":"deadline"}}},{"kind":"Argument","name":{"kind":"Name","value":"nonce"},"value":{"kind":"Variable","name":{"kind":"Name","value":"nonce"}}},{"kind":"Argument","name":{"kind":"Name","value":"signature"},"value":{"kind":"Variable","name":{"kind":"Name","value":"signature"}}},{"kind":"Argument","name":{"kind":"Name","value":"apiToken"},"value":{"kind":"Variable","name":{"kind":"Name","value":"apiToken"}}}]}]}}]} as unknown as DocumentNode<AirDropClaimMutation, AirDropClaimMutationVariables>;
+export const SourceChainIdDocument = {"kind":"Document","definitions":[{"kind":"OperationDefinition","operation":"query","name":{"kind":"Name","value":"SourceChainId"},"selectionSet":{"kind":"SelectionSet","selections":[{"kind":"Field","name":{"kind":"Name","value":"sourceChainId"}}]}}]} as unknown as DocumentNode<SourceChainIdQuery, SourceChainIdQueryVariables>;
\ No newline at end of file